@@ -0,0 +1,34 @@
+-- Drops every table of the initial schema, and all data with it. CASCADE
+-- removes foreign keys between them (applications -> job_pipeline_stages).
+
+DROP TABLE IF EXISTS sessions CASCADE;
+DROP TABLE IF EXISTS webauthn_challenges CASCADE;
+DROP TABLE IF EXISTS webauthn_credentials CASCADE;
+DROP TABLE IF EXISTS impersonation_log CASCADE;
+DROP TABLE IF EXISTS sso_login_states CASCADE;
+DROP TABLE IF EXISTS client_sso_configs CASCADE;
+DROP TABLE IF EXISTS job_assignments CASCADE;
+DROP TABLE IF EXISTS client_invitations CASCADE;
+DROP TABLE IF EXISTS jwt_signing_keys CASCADE;
+DROP TABLE IF EXISTS api_keys CASCADE;
+DROP TABLE IF EXISTS mfa_role_policies CASCADE;
+DROP TABLE IF EXISTS mfa_recovery_codes CASCADE;
+DROP TABLE IF EXISTS user_mfa CASCADE;
+DROP TABLE IF EXISTS user_action_tokens CASCADE;
+DROP TABLE IF EXISTS revoked_access_tokens CASCADE;
+DROP TABLE IF EXISTS refresh_tokens CASCADE;
+DROP TABLE IF EXISTS invoice_line_items CASCADE;
+DROP TABLE IF EXISTS invoices CASCADE;
+DROP TABLE IF EXISTS fee_agreements CASCADE;
+DROP TABLE IF EXISTS placements CASCADE;
+DROP TABLE IF EXISTS offer_revisions CASCADE;
+DROP TABLE IF EXISTS offers CASCADE;
+DROP TABLE IF EXISTS job_pipeline_stages CASCADE;
+DROP TABLE IF EXISTS application_status_history CASCADE;
+DROP TABLE IF EXISTS users CASCADE;
+DROP TABLE IF EXISTS meetings CASCADE;
+DROP TABLE IF EXISTS applications CASCADE;
+DROP TABLE IF EXISTS job_postings CASCADE;
+DROP TABLE IF EXISTS workers CASCADE;
+DROP TABLE IF EXISTS clients CASCADE;
+DROP SEQUENCE IF EXISTS invoice_number_seq;
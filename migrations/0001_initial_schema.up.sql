@@ -0,0 +1,461 @@
+-- Schema as created at startup before versioned migrations. Statements are
+-- idempotent so databases set up by earlier releases are adopted as they are.
+
+-- Clients table
+CREATE TABLE IF NOT EXISTS clients (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    company_name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) UNIQUE NOT NULL,
+    phone VARCHAR(50),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Workers table
+CREATE TABLE IF NOT EXISTS workers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) UNIQUE NOT NULL,
+    phone VARCHAR(50),
+    skills TEXT[] DEFAULT '{}',
+    experience_years INTEGER DEFAULT 0,
+    resume_url VARCHAR(500),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Job postings table
+CREATE TABLE IF NOT EXISTS job_postings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    description TEXT NOT NULL,
+    requirements TEXT[] DEFAULT '{}',
+    salary_range VARCHAR(100),
+    location VARCHAR(255) NOT NULL,
+    job_type VARCHAR(50) DEFAULT 'full-time',
+    is_active BOOLEAN DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Applications table
+CREATE TABLE IF NOT EXISTS applications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id UUID NOT NULL REFERENCES job_postings(id) ON DELETE CASCADE,
+    worker_id UUID NOT NULL REFERENCES workers(id) ON DELETE CASCADE,
+    status VARCHAR(50) DEFAULT 'pending',
+    cover_letter TEXT,
+    applied_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    UNIQUE(job_id, worker_id)
+);
+
+-- Meetings table
+CREATE TABLE IF NOT EXISTS meetings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    worker_id UUID REFERENCES workers(id) ON DELETE SET NULL,
+    job_id UUID REFERENCES job_postings(id) ON DELETE SET NULL,
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    scheduled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    duration_minutes INTEGER DEFAULT 60,
+    status VARCHAR(50) DEFAULT 'scheduled',
+    meeting_url VARCHAR(500),
+    location VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Users table for authentication
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email VARCHAR(255) UNIQUE NOT NULL,
+    password_hash VARCHAR(255) NOT NULL,
+    role VARCHAR(50) NOT NULL,
+    client_id UUID REFERENCES clients(id) ON DELETE CASCADE,
+    worker_id UUID REFERENCES workers(id) ON DELETE CASCADE,
+    is_active BOOLEAN DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Application status history (audit trail of every status change)
+CREATE TABLE IF NOT EXISTS application_status_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    application_id UUID NOT NULL REFERENCES applications(id) ON DELETE CASCADE,
+    from_status VARCHAR(50),
+    to_status VARCHAR(50) NOT NULL,
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    changed_by_email VARCHAR(255),
+    reason TEXT,
+    changed_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Legacy 'accepted' applications map onto the offer stage
+UPDATE applications SET status = 'offer' WHERE status = 'accepted';
+
+-- Per-job hiring pipeline stages
+CREATE TABLE IF NOT EXISTS job_pipeline_stages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id UUID NOT NULL REFERENCES job_postings(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    position INTEGER NOT NULL,
+    is_terminal BOOLEAN NOT NULL DEFAULT false,
+    is_success BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS stage_id UUID REFERENCES job_pipeline_stages(id) ON DELETE SET NULL;
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS stage_entered_at TIMESTAMP WITH TIME ZONE DEFAULT NOW();
+ALTER TABLE applications ADD COLUMN IF NOT EXISTS stage_position INTEGER NOT NULL DEFAULT 0;
+
+-- Give jobs created before pipelines existed the default stages
+INSERT INTO job_pipeline_stages (job_id, name, position, is_terminal, is_success)
+SELECT j.id, d.name, d.position, d.is_terminal, d.is_success
+FROM job_postings j
+CROSS JOIN (VALUES
+    ('Applied', 0, false, false),
+    ('Screening', 1, false, false),
+    ('Interview', 2, false, false),
+    ('Offer', 3, false, false),
+    ('Hired', 4, true, true),
+    ('Rejected', 5, true, false)
+) AS d(name, position, is_terminal, is_success)
+WHERE NOT EXISTS (SELECT 1 FROM job_pipeline_stages s WHERE s.job_id = j.id);
+
+-- Place existing applications on the default stage matching their status
+UPDATE applications a
+SET stage_id = s.id, stage_entered_at = COALESCE(a.updated_at, a.applied_at)
+FROM job_pipeline_stages s
+WHERE a.stage_id IS NULL
+  AND s.job_id = a.job_id
+  AND s.name = CASE a.status
+      WHEN 'reviewing' THEN 'Screening'
+      WHEN 'interview' THEN 'Interview'
+      WHEN 'offer' THEN 'Offer'
+      WHEN 'hired' THEN 'Hired'
+      WHEN 'rejected' THEN 'Rejected'
+      WHEN 'withdrawn' THEN 'Rejected'
+      ELSE 'Applied'
+  END;
+
+-- Optional number of hires after which a job closes itself
+ALTER TABLE job_postings ADD COLUMN IF NOT EXISTS headcount INTEGER;
+
+-- Offers made on applications
+CREATE TABLE IF NOT EXISTS offers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    application_id UUID NOT NULL REFERENCES applications(id) ON DELETE CASCADE,
+    salary BIGINT NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    start_date DATE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'draft',
+    notes TEXT,
+    version INTEGER NOT NULL DEFAULT 1,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    responded_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Snapshot of an offer after every change
+CREATE TABLE IF NOT EXISTS offer_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    offer_id UUID NOT NULL REFERENCES offers(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    event VARCHAR(20) NOT NULL,
+    salary BIGINT NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    start_date DATE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    notes TEXT,
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    changed_by_email VARCHAR(255),
+    reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Placements: hired candidates the agency bills for
+CREATE TABLE IF NOT EXISTS placements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    application_id UUID UNIQUE REFERENCES applications(id) ON DELETE SET NULL,
+    worker_id UUID NOT NULL REFERENCES workers(id) ON DELETE RESTRICT,
+    job_id UUID NOT NULL REFERENCES job_postings(id) ON DELETE RESTRICT,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE RESTRICT,
+    start_date DATE NOT NULL,
+    agreed_salary BIGINT NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Per-client agency fee agreements
+CREATE TABLE IF NOT EXISTS fee_agreements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    client_id UUID UNIQUE NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    fee_type VARCHAR(20) NOT NULL,
+    percentage_bps INTEGER,
+    flat_fee BIGINT,
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    payment_terms_days INTEGER NOT NULL DEFAULT 30,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Invoices and their line items
+CREATE SEQUENCE IF NOT EXISTS invoice_number_seq;
+
+CREATE TABLE IF NOT EXISTS invoices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    invoice_number VARCHAR(30) UNIQUE NOT NULL,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE RESTRICT,
+    placement_id UUID UNIQUE REFERENCES placements(id) ON DELETE RESTRICT,
+    issue_date DATE NOT NULL,
+    due_date DATE NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    subtotal BIGINT NOT NULL,
+    total BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'unpaid',
+    paid_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS invoice_line_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL DEFAULT 0,
+    description TEXT NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 1,
+    unit_amount BIGINT NOT NULL,
+    amount BIGINT NOT NULL
+);
+
+-- Refresh tokens (stored hashed, rotated on every use)
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    family_id UUID NOT NULL,
+    token_hash VARCHAR(64) UNIQUE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    replaced_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Access tokens revoked before their expiry (logout)
+CREATE TABLE IF NOT EXISTS revoked_access_tokens (
+    jti UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Tokens issued before this instant are rejected (password change, log out everywhere)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS sessions_valid_after TIMESTAMP WITH TIME ZONE;
+
+-- Accounts that existed before verification was introduced count as verified
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_verified_at TIMESTAMP WITH TIME ZONE DEFAULT NOW();
+ALTER TABLE users ALTER COLUMN email_verified_at DROP DEFAULT;
+
+-- Single-use tokens sent by email (password reset, email verification, magic links); only the hash is stored
+CREATE TABLE IF NOT EXISTS user_action_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    purpose VARCHAR(30) NOT NULL,
+    token_hash VARCHAR(64) UNIQUE NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- TOTP authenticators; enabled_at stays NULL until the first code is confirmed
+CREATE TABLE IF NOT EXISTS user_mfa (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret VARCHAR(64) NOT NULL,
+    enabled_at TIMESTAMP WITH TIME ZONE,
+    last_used_step BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS mfa_recovery_codes (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- Roles whose accounts must use MFA
+CREATE TABLE IF NOT EXISTS mfa_role_policies (
+    role VARCHAR(20) PRIMARY KEY,
+    require_mfa BOOLEAN NOT NULL DEFAULT false,
+    updated_at TIMESTAMP WITH TIME ZONE,
+    updated_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+INSERT INTO mfa_role_policies (role) VALUES ('admin'), ('client'), ('worker') ON CONFLICT (role) DO NOTHING;
+
+-- Keys for client integrations; only the hash is stored, the prefix identifies the key in listings
+CREATE TABLE IF NOT EXISTS api_keys (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) UNIQUE NOT NULL,
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    expires_at TIMESTAMP WITH TIME ZONE,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Ed25519 keys for access tokens; private keys are encrypted with a key derived from JWT_SECRET
+CREATE TABLE IF NOT EXISTS jwt_signing_keys (
+    kid VARCHAR(32) PRIMARY KEY,
+    private_key BYTEA NOT NULL,
+    activates_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Team role of client users; the single login companies had so far becomes the owner
+ALTER TABLE users ADD COLUMN IF NOT EXISTS team_role VARCHAR(20);
+UPDATE users SET team_role = 'owner' WHERE role = 'client' AND team_role IS NULL;
+
+-- Invitations to join a client organization; only the token hash is stored
+CREATE TABLE IF NOT EXISTS client_invitations (
+    id UUID PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    team_role VARCHAR(20) NOT NULL,
+    token_hash VARCHAR(64) UNIQUE NOT NULL,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Jobs a hiring manager works on
+CREATE TABLE IF NOT EXISTS job_assignments (
+    job_id UUID NOT NULL REFERENCES job_postings(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    assigned_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, user_id)
+);
+
+-- OpenID Connect provider per client company
+CREATE TABLE IF NOT EXISTS client_sso_configs (
+    client_id UUID PRIMARY KEY REFERENCES clients(id) ON DELETE CASCADE,
+    issuer VARCHAR(500) NOT NULL,
+    oidc_client_id VARCHAR(255) NOT NULL,
+    oidc_client_secret VARCHAR(500) NOT NULL,
+    allowed_email_domains TEXT[] NOT NULL DEFAULT '{}',
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Sign-ins waiting for the identity provider to redirect back
+CREATE TABLE IF NOT EXISTS sso_login_states (
+    state_hash VARCHAR(64) PRIMARY KEY,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    code_verifier VARCHAR(128) NOT NULL,
+    nonce VARCHAR(128) NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Account administration: last sign-in, and resets forced by an admin
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_login_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS password_reset_required BOOLEAN NOT NULL DEFAULT false;
+
+-- Requests made by admins while impersonating a user, kept when either account is deleted
+CREATE TABLE IF NOT EXISTS impersonation_log (
+    id UUID PRIMARY KEY,
+    admin_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    token_id UUID NOT NULL,
+    method VARCHAR(10) NOT NULL,
+    path TEXT NOT NULL,
+    status_code INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS webauthn_credentials (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    credential_id VARCHAR(1400) UNIQUE NOT NULL,
+    public_key BYTEA NOT NULL,
+    algorithm INTEGER NOT NULL,
+    sign_count BIGINT NOT NULL DEFAULT 0,
+    name VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE TABLE IF NOT EXISTS webauthn_challenges (
+    challenge VARCHAR(64) PRIMARY KEY,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    purpose VARCHAR(20) NOT NULL CHECK (purpose IN ('registration', 'authentication')),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_agent TEXT,
+    ip_address VARCHAR(45),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_workers_skills ON workers USING GIN(skills);
+CREATE INDEX IF NOT EXISTS idx_job_postings_requirements ON job_postings USING GIN(requirements);
+CREATE INDEX IF NOT EXISTS idx_job_postings_client_id ON job_postings(client_id);
+CREATE INDEX IF NOT EXISTS idx_job_postings_is_active ON job_postings(is_active);
+CREATE INDEX IF NOT EXISTS idx_applications_job_id ON applications(job_id);
+CREATE INDEX IF NOT EXISTS idx_applications_worker_id ON applications(worker_id);
+CREATE INDEX IF NOT EXISTS idx_application_status_history_application_id ON application_status_history(application_id);
+CREATE INDEX IF NOT EXISTS idx_job_pipeline_stages_job_id ON job_pipeline_stages(job_id);
+CREATE INDEX IF NOT EXISTS idx_applications_stage_id ON applications(stage_id);
+CREATE INDEX IF NOT EXISTS idx_offers_application_id ON offers(application_id);
+CREATE INDEX IF NOT EXISTS idx_offers_status_expires_at ON offers(status, expires_at);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_offers_one_open_per_application ON offers(application_id) WHERE status IN ('draft', 'sent');
+CREATE INDEX IF NOT EXISTS idx_offer_revisions_offer_id ON offer_revisions(offer_id);
+CREATE INDEX IF NOT EXISTS idx_placements_client_id ON placements(client_id);
+CREATE INDEX IF NOT EXISTS idx_placements_worker_id ON placements(worker_id);
+CREATE INDEX IF NOT EXISTS idx_invoices_client_id ON invoices(client_id);
+CREATE INDEX IF NOT EXISTS idx_invoices_status ON invoices(status);
+CREATE INDEX IF NOT EXISTS idx_invoice_line_items_invoice_id ON invoice_line_items(invoice_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_family_id ON refresh_tokens(family_id);
+CREATE INDEX IF NOT EXISTS idx_revoked_access_tokens_expires_at ON revoked_access_tokens(expires_at);
+CREATE INDEX IF NOT EXISTS idx_user_action_tokens_user_id ON user_action_tokens(user_id, purpose);
+CREATE INDEX IF NOT EXISTS idx_mfa_recovery_codes_user_id ON mfa_recovery_codes(user_id);
+CREATE INDEX IF NOT EXISTS idx_api_keys_client_id ON api_keys(client_id);
+CREATE INDEX IF NOT EXISTS idx_sso_login_states_expires_at ON sso_login_states(expires_at);
+CREATE INDEX IF NOT EXISTS idx_users_client_id ON users(client_id);
+CREATE INDEX IF NOT EXISTS idx_client_invitations_client_id ON client_invitations(client_id);
+CREATE INDEX IF NOT EXISTS idx_job_assignments_user_id ON job_assignments(user_id);
+CREATE INDEX IF NOT EXISTS idx_users_role ON users(role);
+CREATE INDEX IF NOT EXISTS idx_impersonation_log_user_id ON impersonation_log(user_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_impersonation_log_admin_id ON impersonation_log(admin_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user_id ON webauthn_credentials(user_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_meetings_client_id ON meetings(client_id);
+CREATE INDEX IF NOT EXISTS idx_meetings_worker_id ON meetings(worker_id);
+CREATE INDEX IF NOT EXISTS idx_meetings_scheduled_at ON meetings(scheduled_at);
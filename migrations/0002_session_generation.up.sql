@@ -0,0 +1,4 @@
+-- Bumped whenever a user's sessions are invalidated; access tokens carry the value they were issued with
+ALTER TABLE users ADD COLUMN session_generation INTEGER NOT NULL DEFAULT 0;
+-- Superseded by session_generation
+ALTER TABLE users DROP COLUMN IF EXISTS sessions_valid_after;
@@ -0,0 +1,41 @@
+-- Demo clients, workers and jobs for local development, loaded with
+-- `recruitment-backend seed`. Safe to run more than once.
+
+INSERT INTO clients (company_name, email, phone) VALUES
+('Tech Solutions Inc', 'contact@techsolutions.com', '+1-555-0101'),
+('Global Recruitment', 'info@globalrecruit.com', '+1-555-0102')
+ON CONFLICT (email) DO NOTHING;
+
+INSERT INTO workers (name, email, phone, skills, experience_years) VALUES
+('John Doe', 'john.doe@email.com', '+1-555-0201', ARRAY['JavaScript', 'React', 'Node.js'], 5),
+('Jane Smith', 'jane.smith@email.com', '+1-555-0202', ARRAY['Python', 'Django', 'PostgreSQL'], 3),
+('Mike Johnson', 'mike.johnson@email.com', '+1-555-0203', ARRAY['Java', 'Spring', 'MongoDB'], 7)
+ON CONFLICT (email) DO NOTHING;
+
+INSERT INTO job_postings (client_id, title, description, requirements, salary_range, location)
+SELECT c.id, 'Senior Frontend Developer', 'We are looking for an experienced frontend developer to join our team.',
+       ARRAY['JavaScript', 'React', 'TypeScript'], '$80,000 - $120,000', 'Remote'
+FROM clients c
+WHERE c.email = 'contact@techsolutions.com'
+  AND NOT EXISTS (SELECT 1 FROM job_postings j WHERE j.client_id = c.id AND j.title = 'Senior Frontend Developer');
+
+INSERT INTO job_postings (client_id, title, description, requirements, salary_range, location)
+SELECT c.id, 'Backend Engineer', 'Seeking a skilled backend engineer to work on our core systems.',
+       ARRAY['Python', 'Django', 'PostgreSQL'], '$70,000 - $100,000', 'New York'
+FROM clients c
+WHERE c.email = 'info@globalrecruit.com'
+  AND NOT EXISTS (SELECT 1 FROM job_postings j WHERE j.client_id = c.id AND j.title = 'Backend Engineer');
+
+-- New jobs get their pipeline from the API; seeded ones need it here
+INSERT INTO job_pipeline_stages (job_id, name, position, is_terminal, is_success)
+SELECT j.id, d.name, d.position, d.is_terminal, d.is_success
+FROM job_postings j
+CROSS JOIN (VALUES
+    ('Applied', 0, false, false),
+    ('Screening', 1, false, false),
+    ('Interview', 2, false, false),
+    ('Offer', 3, false, false),
+    ('Hired', 4, true, true),
+    ('Rejected', 5, true, false)
+) AS d(name, position, is_terminal, is_success)
+WHERE NOT EXISTS (SELECT 1 FROM job_pipeline_stages s WHERE s.job_id = j.id);
@@ -4,8 +4,9 @@ echo Setting up recruitment database...
 REM Create database
 createdb -U postgres recruitment
 
-REM Run schema setup
-psql -U postgres -d recruitment -f database.sql
+REM Create the schema and load the demo data
+cargo run -- migrate up
+cargo run -- seed
 
 echo Database setup complete!
 echo You can now run: cargo run
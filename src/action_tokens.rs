@@ -0,0 +1,111 @@
+use chrono::{Duration, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::{hash_token, new_opaque_token};
+use crate::error::AppError;
+
+/// What a single-use emailed token lets its holder do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenPurpose {
+    PasswordReset,
+    EmailVerification,
+    MagicLink,
+}
+
+impl TokenPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenPurpose::PasswordReset => "password_reset",
+            TokenPurpose::EmailVerification => "email_verification",
+            TokenPurpose::MagicLink => "magic_link",
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        match self {
+            TokenPurpose::PasswordReset => Duration::hours(1),
+            TokenPurpose::EmailVerification => Duration::hours(24),
+            TokenPurpose::MagicLink => Duration::minutes(15),
+        }
+    }
+}
+
+/// Creates a token for `user_id`, voiding any unused one with the same purpose so
+/// only the most recent email works. Returns the plaintext token; only its hash is stored.
+pub async fn issue_action_token(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    purpose: TokenPurpose,
+) -> Result<String, AppError> {
+    sqlx::query!(
+        "UPDATE user_action_tokens SET used_at = NOW() WHERE user_id = $1 AND purpose = $2 AND used_at IS NULL",
+        user_id,
+        purpose.as_str()
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let token = new_opaque_token();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO user_action_tokens (id, user_id, purpose, token_hash, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        Uuid::new_v4(),
+        user_id,
+        purpose.as_str(),
+        hash_token(&token),
+        Utc::now() + purpose.ttl()
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(token)
+}
+
+/// Marks the token used and returns its user, or `None` when it is unknown,
+/// expired, already used or meant for something else.
+pub async fn consume_action_token(
+    conn: &mut PgConnection,
+    token: &str,
+    purpose: TokenPurpose,
+) -> Result<Option<Uuid>, AppError> {
+    let user_id = sqlx::query_scalar!(
+        r#"
+        UPDATE user_action_tokens
+        SET used_at = NOW()
+        WHERE token_hash = $1 AND purpose = $2 AND used_at IS NULL AND expires_at > NOW()
+        RETURNING user_id
+        "#,
+        hash_token(token),
+        purpose.as_str()
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(user_id)
+}
+
+/// Like `issue_action_token`, but returns `None` instead when the user was sent a
+/// token for `purpose` less than a minute ago: at most one email a minute per account.
+pub async fn issue_unless_recent(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    purpose: TokenPurpose,
+) -> Result<Option<String>, AppError> {
+    let last_issued_at = sqlx::query_scalar!(
+        "SELECT MAX(created_at) FROM user_action_tokens WHERE user_id = $1 AND purpose = $2",
+        user_id,
+        purpose.as_str()
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if last_issued_at.is_some_and(|issued_at| Utc::now() - issued_at < Duration::minutes(1)) {
+        return Ok(None);
+    }
+
+    issue_action_token(conn, user_id, purpose).await.map(Some)
+}
@@ -0,0 +1,101 @@
+use chrono::Utc;
+use rand::RngCore;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::{hash_token, Claims};
+use crate::error::AppError;
+use crate::permissions::{scope_permission, Permission, Role, TeamRole};
+
+/// Header carrying an API key on integration requests.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+// Keys look like rak_<prefix>_<secret>; the prefix is stored in clear to tell keys apart
+const KEY_MARKER: &str = "rak";
+
+/// A new key and its public prefix. Only the key's hash is stored.
+pub fn new_api_key() -> (String, String) {
+    let mut prefix = [0u8; 4];
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut prefix);
+    rand::thread_rng().fill_bytes(&mut secret);
+
+    let prefix = hex::encode(prefix);
+    let key = format!("{}_{}_{}", KEY_MARKER, prefix, hex::encode(secret));
+    (prefix, key)
+}
+
+/// What a valid API key may do: act for its client within its scopes.
+#[derive(Debug, Clone)]
+pub struct ApiKeyIdentity {
+    pub key_id: Uuid,
+    pub client_id: Uuid,
+    pub created_by: Uuid,
+    pub created_by_email: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl ApiKeyIdentity {
+    /// Claims for the rest of the stack. Requests are attributed to the user who
+    /// created the key, with the client role.
+    pub fn claims(&self) -> Claims {
+        let now = Utc::now().timestamp();
+
+        Claims {
+            sub: self.created_by.to_string(),
+            email: self.created_by_email.clone(),
+            role: Role::Client.as_str().to_string(),
+            client_id: Some(self.client_id),
+            worker_id: None,
+            // Every scope is within what a recruiter may do; the creator is an owner
+            // or an admin, or the key would not have authenticated
+            team_role: Some(TeamRole::Recruiter.as_str().to_string()),
+            impersonated_by: None,
+            sid: None,
+            jti: self.key_id,
+            sgen: 0,
+            iat: now,
+            exp: now,
+        }
+    }
+}
+
+/// Scopes of an API key request, checked by the permission layer on top of the role.
+#[derive(Debug, Clone)]
+pub struct ApiKeyScopes(pub Vec<Permission>);
+
+/// Looks up a presented key and records its use. `None` for unknown, revoked or
+/// expired keys, and for keys whose creator is no longer active or no longer an
+/// admin or an owner of the key's client.
+pub async fn authenticate_api_key(pool: &PgPool, presented: &str) -> Result<Option<ApiKeyIdentity>, AppError> {
+    let prefix = match presented.trim().split('_').collect::<Vec<_>>()[..] {
+        [KEY_MARKER, prefix, _] => prefix.to_string(),
+        _ => return Ok(None),
+    };
+
+    let key = sqlx::query!(
+        r#"
+        UPDATE api_keys k
+        SET last_used_at = NOW()
+        FROM users u
+        WHERE k.prefix = $1 AND k.key_hash = $2
+          AND k.revoked_at IS NULL
+          AND (k.expires_at IS NULL OR k.expires_at > NOW())
+          AND u.id = k.created_by AND u.is_active = true
+          AND (u.role = 'admin' OR (u.role = 'client' AND u.client_id = k.client_id AND u.team_role = 'owner'))
+        RETURNING k.id, k.client_id, k.scopes, k.created_by, u.email
+        "#,
+        prefix,
+        hash_token(presented.trim())
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(key.map(|key| ApiKeyIdentity {
+        key_id: key.id,
+        client_id: key.client_id,
+        created_by: key.created_by,
+        created_by_email: key.email,
+        permissions: key.scopes.iter().filter_map(|scope| scope_permission(scope)).collect(),
+    }))
+}
@@ -1,71 +1,309 @@
-use bcrypt::{hash, verify, DEFAULT_COST};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::{Utc, Duration};
-use crate::models::{User, LoginRequest, LoginResponse, CreateUserRequest};
+use chrono::{DateTime, Duration, Utc};
+use crate::models::{User, LoginRequest, LoginResponse, LoginResult, MfaChallengeResponse, CreateUserRequest, Passkey, Session};
+use crate::mfa;
+use crate::passwords::{self, Argon2Config, PasswordPolicy};
+use crate::api_keys::{authenticate_api_key, ApiKeyScopes, API_KEY_HEADER};
 use crate::error::AppError;
-use sqlx::PgPool;
+use crate::permissions::{Role, TeamRole};
+use crate::signing_keys::{sync_signing_keys, KeyRing};
+use crate::webauthn::{self, AssertionCredential, RegistrationCredential, RelyingParty};
+use data_encoding::BASE64URL_NOPAD;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user id
     pub email: String,
     pub role: String,
+    pub client_id: Option<Uuid>, // users.client_id, for ownership checks
+    pub worker_id: Option<Uuid>, // users.worker_id, for ownership checks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_role: Option<String>, // users.team_role, client users only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<Uuid>, // the admin acting as this user, see `issue_impersonation_token`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>, // sessions.id, the login this token belongs to
+    pub jti: Uuid, // token id, so a single access token can be revoked
+    #[serde(default)]
+    pub sgen: i32, // users.session_generation when issued, compared on every request
+    pub iat: i64, // issued at
     pub exp: i64, // expiration time
 }
 
+/// Proof that the first step of a login (password or magic link) succeeded,
+/// exchanged for a session together with a second factor. The audience keeps it from passing as an access token.
+#[derive(Debug, Serialize, Deserialize)]
+struct MfaChallengeClaims {
+    sub: Uuid,
+    aud: String,
+    exp: i64,
+}
+
+const MFA_CHALLENGE_AUDIENCE: &str = "mfa-challenge";
+const MFA_CHALLENGE_TTL_MINUTES: i64 = 5;
+
+/// What an account may do before its email address is verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailVerificationPolicy {
+    /// No restriction, verification is informational
+    Optional,
+    /// Logged in, but routes that change data are refused
+    RestrictActions,
+    /// Unverified accounts cannot log in
+    RequiredForLogin,
+}
+
+impl EmailVerificationPolicy {
+    /// Values accepted in `EMAIL_VERIFICATION_POLICY`.
+    pub fn parse(policy: &str) -> Option<Self> {
+        match policy {
+            "optional" => Some(EmailVerificationPolicy::Optional),
+            "restrict" => Some(EmailVerificationPolicy::RestrictActions),
+            "login" => Some(EmailVerificationPolicy::RequiredForLogin),
+            _ => None,
+        }
+    }
+}
+
+/// Marks requests from unverified accounts under `RestrictActions`, for the permission layer.
+#[derive(Debug, Clone, Copy)]
+pub struct UnverifiedEmail;
+
+/// Outcome of checking an access token against the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    Invalid,
+    Active { email_verified: bool },
+}
+
+#[derive(Clone)]
 pub struct AuthService {
+    // Encrypts the stored signing keys; tokens are signed with `keys`
     jwt_secret: String,
+    keys: KeyRing,
+    key_rotation: Duration,
+    access_token_ttl: Duration,
+    refresh_token_ttl: Duration,
+    email_verification: EmailVerificationPolicy,
+    argon2: Argon2Config,
+    password_policy: Arc<PasswordPolicy>,
+    relying_party: RelyingParty,
+}
+
+// Refresh and emailed tokens are opaque random strings; only their SHA-256 is stored
+pub(crate) fn new_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
 }
 
 impl AuthService {
     pub fn new(jwt_secret: String) -> Self {
-        Self { jwt_secret }
+        Self {
+            jwt_secret,
+            keys: KeyRing::default(),
+            key_rotation: Duration::days(30),
+            access_token_ttl: Duration::minutes(15),
+            refresh_token_ttl: Duration::days(30),
+            email_verification: EmailVerificationPolicy::Optional,
+            argon2: Argon2Config::default(),
+            password_policy: Arc::new(PasswordPolicy::default()),
+            relying_party: RelyingParty::from_origin("http://localhost:3000", "Recruitment Agency")
+                .expect("valid origin"),
+        }
+    }
+
+    pub fn with_token_ttls(mut self, access_token_ttl: Duration, refresh_token_ttl: Duration) -> Self {
+        self.access_token_ttl = access_token_ttl;
+        self.refresh_token_ttl = refresh_token_ttl;
+        self
+    }
+
+    pub fn with_key_rotation(mut self, key_rotation: Duration) -> Self {
+        self.key_rotation = key_rotation;
+        self
+    }
+
+    pub fn with_email_verification(mut self, policy: EmailVerificationPolicy) -> Self {
+        self.email_verification = policy;
+        self
+    }
+
+    pub fn with_password_hashing(mut self, argon2: Argon2Config, policy: PasswordPolicy) -> Self {
+        self.argon2 = argon2;
+        self.password_policy = Arc::new(policy);
+        self
+    }
+
+    pub fn with_relying_party(mut self, relying_party: RelyingParty) -> Self {
+        self.relying_party = relying_party;
+        self
+    }
+
+    pub fn email_verification(&self) -> EmailVerificationPolicy {
+        self.email_verification
+    }
+
+    pub fn access_token_ttl(&self) -> Duration {
+        self.access_token_ttl
+    }
+
+    /// Rules new passwords are checked against.
+    pub fn password_policy(&self) -> &PasswordPolicy {
+        &self.password_policy
+    }
+
+    /// Argon2 is slow on purpose, so hashing runs on the blocking pool rather than
+    /// holding up other requests on the async workers.
+    pub async fn hash_password(&self, password: &str) -> Result<String, AppError> {
+        let argon2 = self.argon2;
+        let password = password.to_string();
+
+        tokio::task::spawn_blocking(move || passwords::hash_password(&argon2, &password))
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Password hashing failed: {}", e)))?
+    }
+
+    /// Checks a password on the blocking pool, like `hash_password`.
+    pub async fn verify_password(&self, password: &str, hash: &str) -> Result<bool, AppError> {
+        let password = password.to_string();
+        let hash = hash.to_string();
+
+        tokio::task::spawn_blocking(move || passwords::verify_password(&password, &hash))
+            .await
+            .map_err(|e| AppError::InternalServerError(format!("Password verification failed: {}", e)))?
+    }
+
+    /// Rotates and reloads the signing keys. Must run once before tokens can be issued.
+    pub async fn sync_signing_keys(&self, pool: &PgPool) -> Result<(), AppError> {
+        // A rotated-out key stays published for as long as its tokens can live
+        let retention = self.access_token_ttl.max(Duration::minutes(MFA_CHALLENGE_TTL_MINUTES)) + Duration::minutes(5);
+        sync_signing_keys(pool, &self.keys, &self.jwt_secret, self.key_rotation, retention).await
+    }
+
+    /// Public keys for `/.well-known/jwks.json`.
+    pub fn jwks(&self) -> serde_json::Value {
+        self.keys.jwks()
     }
 
-    pub fn hash_password(&self, password: &str) -> Result<String, AppError> {
-        hash(password, DEFAULT_COST)
-            .map_err(|e| AppError::InternalServerError(format!("Failed to hash password: {}", e)))
+    fn sign<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        let (kid, key) = self
+            .keys
+            .signing_key(Utc::now())
+            .ok_or_else(|| AppError::InternalServerError("No signing key loaded".to_string()))?;
+
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(kid);
+
+        encode(&header, claims, &key)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to generate token: {}", e)))
     }
 
-    pub fn verify_password(&self, password: &str, hash: &str) -> Result<bool, AppError> {
-        verify(password, hash)
-            .map_err(|e| AppError::InternalServerError(format!("Failed to verify password: {}", e)))
+    fn verification_key(&self, token: &str) -> Result<DecodingKey, AppError> {
+        let header = decode_header(token).map_err(|_| AppError::Unauthorized)?;
+
+        if header.alg != Algorithm::EdDSA {
+            return Err(AppError::Unauthorized);
+        }
+
+        header
+            .kid
+            .and_then(|kid| self.keys.decoding_key(&kid))
+            .ok_or(AppError::Unauthorized)
     }
 
-    pub fn generate_token(&self, user: &User) -> Result<String, AppError> {
-        let expiration = Utc::now()
-            .checked_add_signed(Duration::days(7))
+    fn access_claims(&self, user: &User) -> Claims {
+        let now = Utc::now();
+        let expiration = now
+            .checked_add_signed(self.access_token_ttl)
             .expect("valid timestamp")
             .timestamp();
 
-        let claims = Claims {
+        Claims {
             sub: user.id.to_string(),
             email: user.email.clone(),
             role: user.role.clone(),
+            client_id: user.client_id,
+            worker_id: user.worker_id,
+            team_role: user.team_role.clone(),
+            impersonated_by: None,
+            sid: None,
+            jti: Uuid::new_v4(),
+            sgen: user.session_generation,
+            iat: now.timestamp(),
             exp: expiration,
+        }
+    }
+
+    pub fn generate_token(&self, user: &User, session_id: Uuid) -> Result<String, AppError> {
+        let claims = Claims {
+            sid: Some(session_id),
+            ..self.access_claims(user)
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.jwt_secret.as_ref()),
-        )
-        .map_err(|e| AppError::InternalServerError(format!("Failed to generate token: {}", e)))
+        self.sign(&claims)
+    }
+
+    /// An access token for `user` that names the admin acting as them. It has no
+    /// session or refresh token, stops working when the admin loses access, and every
+    /// request made with it is written to the impersonation log. Returns the
+    /// token and its `jti`.
+    pub fn issue_impersonation_token(&self, user: &User, admin_id: Uuid) -> Result<(String, Uuid), AppError> {
+        let claims = Claims {
+            impersonated_by: Some(admin_id),
+            ..self.access_claims(user)
+        };
+
+        Ok((self.sign(&claims)?, claims.jti))
     }
 
     pub fn verify_token(&self, token: &str) -> Result<Claims, AppError> {
         let token_data = decode::<Claims>(
             token,
-            &DecodingKey::from_secret(self.jwt_secret.as_ref()),
-            &Validation::default(),
+            &self.verification_key(token)?,
+            &Validation::new(Algorithm::EdDSA),
         )
         .map_err(|_| AppError::Unauthorized)?;
 
         Ok(token_data.claims)
     }
 
+    /// Short-lived token handed out instead of a session when MFA is needed.
+    pub fn issue_mfa_challenge(&self, user_id: Uuid) -> Result<String, AppError> {
+        let claims = MfaChallengeClaims {
+            sub: user_id,
+            aud: MFA_CHALLENGE_AUDIENCE.to_string(),
+            exp: (Utc::now() + Duration::minutes(MFA_CHALLENGE_TTL_MINUTES)).timestamp(),
+        };
+
+        self.sign(&claims)
+    }
+
+    /// The user an MFA challenge token was issued to.
+    pub fn verify_mfa_challenge(&self, token: &str) -> Result<Uuid, AppError> {
+        let mut validation = Validation::new(Algorithm::EdDSA);
+        validation.set_audience(&[MFA_CHALLENGE_AUDIENCE]);
+
+        let token_data = decode::<MfaChallengeClaims>(
+            token,
+            &self.verification_key(token)?,
+            &validation,
+        )
+        .map_err(|_| AppError::Unauthorized)?;
+
+        Ok(token_data.claims.sub)
+    }
+
     pub async fn register_user(
         &self,
         pool: &PgPool,
@@ -84,15 +322,17 @@ impl AuthService {
             return Err(AppError::BadRequest("User with this email already exists".to_string()));
         }
 
-        let password_hash = self.hash_password(&request.password)?;
+        let password_hash = self.hash_password(&request.password).await?;
         let user_id = Uuid::new_v4();
 
         let user = sqlx::query_as!(
             User,
             r#"
-            INSERT INTO users (id, email, password_hash, role, client_id, worker_id)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, email, password_hash, role, client_id, worker_id, is_active, created_at, updated_at
+            INSERT INTO users (id, email, password_hash, role, client_id, worker_id, team_role)
+            VALUES ($1, $2, $3, $4, $5, $6, CASE WHEN $4 = 'client' THEN 'owner' END)
+            RETURNING id, email, password_hash, role, client_id, worker_id, is_active, created_at, updated_at,
+                      email_verified_at, team_role, last_login_at, password_reset_required,
+                      session_generation
             "#,
             user_id,
             request.email,
@@ -107,11 +347,13 @@ impl AuthService {
         Ok(user)
     }
 
+    /// Checks the password, then continues with `complete_first_factor`.
     pub async fn authenticate_user(
         &self,
         pool: &PgPool,
         request: LoginRequest,
-    ) -> Result<LoginResponse, AppError> {
+        device: &SessionDevice,
+    ) -> Result<LoginResult, AppError> {
         let user = sqlx::query_as!(
             User,
             "SELECT * FROM users WHERE email = $1 AND is_active = true",
@@ -121,15 +363,617 @@ impl AuthService {
         .await?
         .ok_or(AppError::Unauthorized)?;
 
-        let is_valid = self.verify_password(&request.password, &user.password_hash)?;
+        let is_valid = self.verify_password(&request.password, &user.password_hash).await?;
 
         if !is_valid {
             return Err(AppError::Unauthorized);
         }
 
-        let token = self.generate_token(&user)?;
+        // Bcrypt hashes, and Argon2 hashes with outdated parameters, are replaced
+        // while the plaintext is at hand
+        if passwords::needs_rehash(&self.argon2, &user.password_hash) {
+            let upgraded = self.hash_password(&request.password).await?;
+
+            sqlx::query!(
+                "UPDATE users SET password_hash = $1 WHERE id = $2 AND password_hash = $3",
+                upgraded,
+                user.id,
+                user.password_hash
+            )
+            .execute(pool)
+            .await?;
+        }
+
+        // Set by an admin who suspects the password leaked; a reset link was emailed
+        if user.password_reset_required {
+            return Err(AppError::PasswordResetRequired);
+        }
+
+        // Only after the password check, so the answer doesn't reveal unverified accounts
+        if self.email_verification == EmailVerificationPolicy::RequiredForLogin && user.email_verified_at.is_none() {
+            return Err(AppError::EmailNotVerified);
+        }
+
+        self.complete_first_factor(pool, user, device).await
+    }
+
+    /// Once the user proved the first factor (password or magic link): a session,
+    /// or an MFA challenge when the user has MFA enabled or their role requires it.
+    pub async fn complete_first_factor(
+        &self,
+        pool: &PgPool,
+        user: User,
+        device: &SessionDevice,
+    ) -> Result<LoginResult, AppError> {
+        let mut conn = pool.acquire().await?;
+        let mfa_enabled = mfa::mfa_settings(&mut conn, user.id)
+            .await?
+            .is_some_and(|settings| settings.enabled);
+
+        if mfa_enabled || mfa::role_requires_mfa(&mut conn, &user.role).await? {
+            return Ok(LoginResult::MfaRequired(MfaChallengeResponse {
+                mfa_required: true,
+                mfa_setup_required: !mfa_enabled,
+                mfa_token: self.issue_mfa_challenge(user.id)?,
+                expires_in: Duration::minutes(MFA_CHALLENGE_TTL_MINUTES).num_seconds(),
+            }));
+        }
+
+        Ok(LoginResult::Session(self.issue_session(pool, user, device).await?))
+    }
+
+    async fn store_refresh_token(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        family_id: Uuid,
+    ) -> Result<(Uuid, String), AppError> {
+        let id = Uuid::new_v4();
+        let token = new_opaque_token();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, family_id, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            id,
+            user_id,
+            family_id,
+            hash_token(&token),
+            Utc::now() + self.refresh_token_ttl
+        )
+        .execute(conn)
+        .await?;
+
+        Ok((id, token))
+    }
+
+    /// Starts a session on `device`: an access token and the first refresh token
+    /// of a new family, whose id is the session id. Records the sign-in as the
+    /// user's last login.
+    pub async fn issue_session(
+        &self,
+        pool: &PgPool,
+        user: User,
+        device: &SessionDevice,
+    ) -> Result<LoginResponse, AppError> {
+        let mut tx = pool.begin().await?;
+        let session_id = Uuid::new_v4();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO sessions (id, user_id, user_agent, ip_address, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            session_id,
+            user.id,
+            device.user_agent,
+            device.ip_address,
+            Utc::now() + self.refresh_token_ttl
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let (_, refresh_token) = self.store_refresh_token(&mut tx, user.id, session_id).await?;
+
+        sqlx::query!("UPDATE users SET last_login_at = NOW() WHERE id = $1", user.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let token = self.generate_token(&user, session_id)?;
+
+        Ok(LoginResponse {
+            token,
+            refresh_token,
+            expires_in: self.access_token_ttl.num_seconds(),
+            user,
+        })
+    }
+
+    async fn store_passkey_challenge(
+        &self,
+        pool: &PgPool,
+        user_id: Option<Uuid>,
+        purpose: &str,
+    ) -> Result<String, AppError> {
+        let challenge = webauthn::new_challenge();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webauthn_challenges (challenge, user_id, purpose, expires_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            challenge,
+            user_id,
+            purpose,
+            Utc::now() + Duration::minutes(webauthn::CHALLENGE_TTL_MINUTES)
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(challenge)
+    }
+
+    /// Deletes a pending challenge so it can be answered only once. False when it
+    /// is unknown, expired, or was issued for another user or ceremony. Runs on its
+    /// own, outside the ceremony's transaction, so a failed answer still uses it up.
+    async fn consume_passkey_challenge(
+        &self,
+        pool: &PgPool,
+        challenge: &str,
+        user_id: Option<Uuid>,
+        purpose: &str,
+    ) -> Result<bool, AppError> {
+        let consumed = sqlx::query_scalar!(
+            r#"
+            DELETE FROM webauthn_challenges
+            WHERE challenge = $1 AND purpose = $2 AND user_id IS NOT DISTINCT FROM $3 AND expires_at > NOW()
+            RETURNING challenge
+            "#,
+            challenge,
+            purpose,
+            user_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(consumed.is_some())
+    }
+
+    /// Options for `navigator.credentials.create()`. The user's existing passkeys
+    /// are excluded so an authenticator isn't registered twice.
+    pub async fn start_passkey_registration(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        email: &str,
+    ) -> Result<serde_json::Value, AppError> {
+        let existing = sqlx::query_scalar!(
+            "SELECT credential_id FROM webauthn_credentials WHERE user_id = $1",
+            user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let challenge = self.store_passkey_challenge(pool, Some(user_id), "registration").await?;
+
+        Ok(webauthn::creation_options(&self.relying_party, user_id, email, &challenge, &existing))
+    }
+
+    /// Verifies the new credential against the user's pending challenge and stores its public key.
+    pub async fn finish_passkey_registration(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        credential: &RegistrationCredential,
+    ) -> Result<Passkey, AppError> {
+        let challenge = webauthn::client_data_challenge(&credential.response.client_data_json)?;
+
+        if !self.consume_passkey_challenge(pool, &challenge, Some(user_id), "registration").await? {
+            return Err(AppError::BadRequest("Passkey registration expired, start again".to_string()));
+        }
+
+        let verified = webauthn::verify_registration(&self.relying_party, &challenge, credential)?;
+
+        let mut tx = pool.begin().await?;
+
+        let registered = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM webauthn_credentials WHERE credential_id = $1",
+            verified.credential_id
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .unwrap_or(0);
+
+        if registered > 0 {
+            return Err(AppError::Conflict("This passkey is already registered".to_string()));
+        }
+
+        let passkey = sqlx::query_as!(
+            Passkey,
+            r#"
+            INSERT INTO webauthn_credentials (id, user_id, credential_id, public_key, algorithm, sign_count, name)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, name, created_at, last_used_at
+            "#,
+            Uuid::new_v4(),
+            user_id,
+            verified.credential_id,
+            verified.public_key,
+            verified.algorithm as i32,
+            verified.sign_count as i64,
+            name
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(passkey)
+    }
+
+    /// Options for `navigator.credentials.get()`. Logins are usernameless: the
+    /// authenticator picks the passkey and its user handle names the account.
+    pub async fn start_passkey_login(&self, pool: &PgPool) -> Result<serde_json::Value, AppError> {
+        let challenge = self.store_passkey_challenge(pool, None, "authentication").await?;
+
+        Ok(webauthn::request_options(&self.relying_party, &challenge))
+    }
+
+    /// Verifies a signed assertion and starts a session. Passkeys require user
+    /// verification (PIN or biometrics) on top of possession of the key, so they
+    /// count as multi-factor and no TOTP code is asked for.
+    pub async fn finish_passkey_login(
+        &self,
+        pool: &PgPool,
+        credential: &AssertionCredential,
+        device: &SessionDevice,
+    ) -> Result<LoginResponse, AppError> {
+        let challenge = webauthn::client_data_challenge(&credential.response.client_data_json)
+            .map_err(|_| AppError::Unauthorized)?;
+
+        if !self.consume_passkey_challenge(pool, &challenge, None, "authentication").await? {
+            return Err(AppError::Unauthorized);
+        }
+
+        let mut tx = pool.begin().await?;
 
-        Ok(LoginResponse { token, user })
+        let stored = sqlx::query!(
+            r#"
+            SELECT id, user_id, public_key, algorithm, sign_count
+            FROM webauthn_credentials
+            WHERE credential_id = $1
+            FOR UPDATE
+            "#,
+            credential.id.trim_end_matches('=')
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+        // The user handle, when sent, is the id the passkey was created for
+        if let Some(handle) = &credential.response.user_handle {
+            let owner = BASE64URL_NOPAD.encode(stored.user_id.as_bytes());
+            if handle.trim_end_matches('=') != owner {
+                return Err(AppError::Unauthorized);
+            }
+        }
+
+        let sign_count = webauthn::verify_assertion(
+            &self.relying_party,
+            &challenge,
+            credential,
+            stored.algorithm as i64,
+            &stored.public_key,
+            stored.sign_count as u32,
+        )?;
+
+        sqlx::query!(
+            "UPDATE webauthn_credentials SET sign_count = $1, last_used_at = NOW() WHERE id = $2",
+            sign_count as i64,
+            stored.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let user = sqlx::query_as!(
+            User,
+            "SELECT * FROM users WHERE id = $1 AND is_active = true",
+            stored.user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+        tx.commit().await?;
+
+        if user.password_reset_required {
+            return Err(AppError::PasswordResetRequired);
+        }
+
+        if self.email_verification == EmailVerificationPolicy::RequiredForLogin && user.email_verified_at.is_none() {
+            return Err(AppError::EmailNotVerified);
+        }
+
+        self.issue_session(pool, user, device).await
+    }
+
+    /// Exchanges a refresh token for a new access/refresh pair. Each refresh token
+    /// works once; presenting one that was already rotated or revoked means it
+    /// leaked, so every token of its family is revoked.
+    pub async fn rotate_refresh_token(&self, pool: &PgPool, presented: &str) -> Result<LoginResponse, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let stored = sqlx::query!(
+            r#"
+            SELECT id, user_id, family_id, expires_at, used_at, revoked_at
+            FROM refresh_tokens
+            WHERE token_hash = $1
+            FOR UPDATE
+            "#,
+            hash_token(presented)
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+        if stored.used_at.is_some() || stored.revoked_at.is_some() {
+            revoke_family(&mut tx, stored.family_id).await?;
+            tx.commit().await?;
+            tracing::warn!("Refresh token reuse detected for user {}, family revoked", stored.user_id);
+            return Err(AppError::Unauthorized);
+        }
+
+        if stored.expires_at <= Utc::now() {
+            return Err(AppError::Unauthorized);
+        }
+
+        let user = sqlx::query_as!(
+            User,
+            "SELECT * FROM users WHERE id = $1 AND is_active = true",
+            stored.user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+        if self.email_verification == EmailVerificationPolicy::RequiredForLogin && user.email_verified_at.is_none() {
+            return Err(AppError::EmailNotVerified);
+        }
+
+        let (next_id, refresh_token) = self.store_refresh_token(&mut tx, user.id, stored.family_id).await?;
+
+        // Families from before sessions were tracked get one, with an unknown device
+        sqlx::query!(
+            r#"
+            INSERT INTO sessions (id, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (id) DO UPDATE SET last_seen_at = NOW(), expires_at = EXCLUDED.expires_at
+            "#,
+            stored.family_id,
+            user.id,
+            Utc::now() + self.refresh_token_ttl
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE refresh_tokens SET used_at = NOW(), replaced_by = $1 WHERE id = $2",
+            next_id,
+            stored.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let token = self.generate_token(&user, stored.family_id)?;
+
+        Ok(LoginResponse {
+            token,
+            refresh_token,
+            expires_in: self.access_token_ttl.num_seconds(),
+            user,
+        })
+    }
+
+    /// Revokes the family of a refresh token owned by `user_id`. Unknown tokens are ignored.
+    pub async fn revoke_refresh_token(&self, pool: &PgPool, user_id: Uuid, presented: &str) -> Result<(), AppError> {
+        let mut conn = pool.acquire().await?;
+
+        let family_id = sqlx::query_scalar!(
+            "SELECT family_id FROM refresh_tokens WHERE token_hash = $1 AND user_id = $2",
+            hash_token(presented),
+            user_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        if let Some(family_id) = family_id {
+            revoke_family(&mut conn, family_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Denylists an access token until it would have expired anyway.
+    pub async fn revoke_access_token(&self, pool: &PgPool, claims: &Claims) -> Result<(), AppError> {
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized)?;
+        let expires_at = DateTime::<Utc>::from_timestamp(claims.exp, 0)
+            .ok_or(AppError::Unauthorized)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO revoked_access_tokens (jti, user_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (jti) DO NOTHING
+            "#,
+            claims.jti,
+            user_id,
+            expires_at
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether an access token may still be used: it has not been logged out, its
+    /// session has not been revoked, its user is active, and it was issued after
+    /// the user's sessions were last invalidated and still carries the user's
+    /// current role. Impersonation
+    /// tokens also need their admin to still be an active admin. Valid sessions
+    /// also report whether the user's email is verified.
+    pub async fn session_status(&self, pool: &PgPool, claims: &Claims) -> Result<SessionStatus, AppError> {
+        let user_id = match Uuid::parse_str(&claims.sub) {
+            Ok(user_id) => user_id,
+            Err(_) => return Ok(SessionStatus::Invalid),
+        };
+
+        let session = sqlx::query!(
+            r#"
+            SELECT u.is_active, u.role, u.session_generation, u.email_verified_at,
+                   EXISTS(SELECT 1 FROM revoked_access_tokens r WHERE r.jti = $2) as "revoked!",
+                   EXISTS(
+                       SELECT 1 FROM users a WHERE a.id = $3 AND a.role = 'admin' AND a.is_active = true
+                   ) as "impersonator_active!",
+                   EXISTS(
+                       SELECT 1 FROM sessions s WHERE s.id = $4 AND s.user_id = u.id AND s.revoked_at IS NULL
+                   ) as "session_active!"
+            FROM users u
+            WHERE u.id = $1
+            "#,
+            user_id,
+            claims.jti,
+            claims.impersonated_by,
+            claims.sid
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(session) = session else {
+            return Ok(SessionStatus::Invalid);
+        };
+
+        // A counter rather than a timestamp, so a token issued in the same instant as
+        // the invalidation cannot slip through
+        let issued_before_invalidation = claims.sgen != session.session_generation;
+
+        let valid = session.is_active.unwrap_or(false)
+            && session.role == claims.role
+            && !session.revoked
+            && !issued_before_invalidation
+            && (claims.impersonated_by.is_none() || session.impersonator_active)
+            && (claims.sid.is_none() || session.session_active);
+
+        if !valid {
+            return Ok(SessionStatus::Invalid);
+        }
+
+        Ok(SessionStatus::Active {
+            email_verified: session.email_verified_at.is_some(),
+        })
+    }
+
+    /// Records activity on a session, at most once a minute so busy clients don't
+    /// write on every request.
+    pub async fn touch_session(&self, pool: &PgPool, session_id: Uuid) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE sessions SET last_seen_at = NOW()
+            WHERE id = $1 AND last_seen_at < NOW() - INTERVAL '1 minute'
+            "#,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The user's sessions that can still be used, most recently active first.
+    pub async fn list_sessions(
+        &self,
+        pool: &PgPool,
+        user_id: Uuid,
+        current_session: Option<Uuid>,
+    ) -> Result<Vec<Session>, AppError> {
+        let sessions = sqlx::query_as!(
+            Session,
+            r#"
+            SELECT id, user_agent, ip_address, created_at, last_seen_at,
+                   COALESCE(id = $2, false) as "current!"
+            FROM sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY last_seen_at DESC
+            "#,
+            user_id,
+            current_session
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Ends one session of a user: its access tokens stop working at their next
+    /// request and its refresh tokens are revoked.
+    pub async fn revoke_session(&self, pool: &PgPool, user_id: Uuid, session_id: Uuid) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+
+        let owned = sqlx::query_scalar!(
+            "SELECT id FROM sessions WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+            session_id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if owned.is_none() {
+            return Err(AppError::NotFound);
+        }
+
+        revoke_family(&mut tx, session_id).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Deletes refresh tokens and denylist entries that can no longer be used.
+    pub async fn purge_expired_tokens(&self, pool: &PgPool) -> Result<u64, AppError> {
+        let refresh = sqlx::query!("DELETE FROM refresh_tokens WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        let revoked = sqlx::query!("DELETE FROM revoked_access_tokens WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        let action = sqlx::query!("DELETE FROM user_action_tokens WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        let sso = sqlx::query!("DELETE FROM sso_login_states WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        let passkey = sqlx::query!("DELETE FROM webauthn_challenges WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        let sessions = sqlx::query!("DELETE FROM sessions WHERE expires_at < NOW()")
+            .execute(pool)
+            .await?;
+
+        Ok(refresh.rows_affected()
+            + revoked.rows_affected()
+            + action.rows_affected()
+            + sso.rows_affected()
+            + passkey.rows_affected()
+            + sessions.rows_affected())
     }
 
     pub async fn get_user_by_id(&self, pool: &PgPool, user_id: Uuid) -> Result<User, AppError> {
@@ -146,6 +990,52 @@ impl AuthService {
     }
 }
 
+/// Ends every session of a user: access tokens issued before now stop working and
+/// all refresh tokens are revoked. Call on password, role or activation changes.
+pub async fn invalidate_sessions(conn: &mut PgConnection, user_id: Uuid) -> Result<(), AppError> {
+    sqlx::query!(
+        "UPDATE users SET session_generation = session_generation + 1 WHERE id = $1",
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE sessions SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Revokes a refresh token family and the session it belongs to.
+async fn revoke_family(conn: &mut PgConnection, family_id: Uuid) -> Result<(), AppError> {
+    sqlx::query!(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = $1 AND revoked_at IS NULL",
+        family_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL",
+        family_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
 // Middleware for JWT authentication
 use axum::{
     extract::{Request, State},
@@ -156,6 +1046,7 @@ use axum::{
 
 pub async fn auth_middleware(
     State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -177,20 +1068,85 @@ pub async fn auth_middleware(
         .verify_token(token)
         .map_err(|_| StatusCode::UNAUTHORIZED)?;
 
+    // Logged-out or invalidated tokens keep a valid signature until they expire
+    let status = auth_service
+        .session_status(&pool, &claims)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let email_verified = match status {
+        SessionStatus::Invalid => return Err(StatusCode::UNAUTHORIZED),
+        SessionStatus::Active { email_verified } => email_verified,
+    };
+
+    if !email_verified {
+        match auth_service.email_verification() {
+            EmailVerificationPolicy::Optional => {}
+            EmailVerificationPolicy::RestrictActions => {
+                request.extensions_mut().insert(UnverifiedEmail);
+            }
+            // Tokens issued before the policy was switched on
+            EmailVerificationPolicy::RequiredForLogin => return Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+
+    if let Some(session_id) = claims.sid {
+        auth_service
+            .touch_session(&pool, session_id)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
     // Add user info to request extensions
     request.extensions_mut().insert(claims);
 
     Ok(next.run(request).await)
 }
 
+/// Like `auth_middleware`, but also accepts a client API key in `X-Api-Key`.
+/// Keys act for their client and only within their scopes.
+pub async fn auth_or_api_key_middleware(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let api_key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|header| header.to_str().ok())
+        .map(str::to_string);
+
+    let Some(api_key) = api_key else {
+        return auth_middleware(State(auth_service), State(pool), request, next).await;
+    };
+
+    let identity = authenticate_api_key(&pool, &api_key)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    request.extensions_mut().insert(identity.claims());
+    request.extensions_mut().insert(ApiKeyScopes(identity.permissions));
+
+    Ok(next.run(request).await)
+}
+
 // Extractor for getting current user from request
-use axum::extract::FromRequestParts;
-use http::request::Parts;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use http::{header::USER_AGENT, request::Parts};
+use std::convert::Infallible;
+use std::net::SocketAddr;
 
 pub struct CurrentUser {
     pub id: Uuid,
     pub email: String,
-    pub role: String,
+    pub role: Role,
+    pub client_id: Option<Uuid>,
+    pub worker_id: Option<Uuid>,
+    pub team_role: Option<TeamRole>,
+    pub impersonated_by: Option<Uuid>,
+    pub session_id: Option<Uuid>,
 }
 
 impl<S> FromRequestParts<S> for CurrentUser
@@ -208,10 +1164,50 @@ where
         let id = Uuid::parse_str(&claims.sub)
             .map_err(|_| StatusCode::UNAUTHORIZED)?;
 
+        let role = Role::parse(&claims.role)
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+
         Ok(CurrentUser {
             id,
             email: claims.email.clone(),
-            role: claims.role.clone(),
+            role,
+            client_id: claims.client_id,
+            worker_id: claims.worker_id,
+            team_role: TeamRole::from_claims(claims),
+            impersonated_by: claims.impersonated_by,
+            session_id: claims.sid,
         })
     }
 }
+
+// Longer user agents are cut, they are only shown to the user
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Where a login comes from, recorded with the session it starts.
+#[derive(Debug, Clone, Default)]
+pub struct SessionDevice {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for SessionDevice
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|header| header.to_str().ok())
+            .map(|agent| agent.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+        Ok(SessionDevice { user_agent, ip_address })
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -11,11 +11,22 @@ pub enum AppError {
     NotFound,
     BadRequest(String),
     Unauthorized,
+    Forbidden,
+    EmailNotVerified,
+    PasswordResetRequired,
+    NotWhileImpersonating,
+    Conflict(String),
+    TooManyRequests(u64), // seconds until the client may retry
     InternalServerError(String),
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        let retry_after = match &self {
+            AppError::TooManyRequests(secs) => Some(*secs),
+            _ => None,
+        };
+
         let (status, error_message) = match self {
             AppError::DatabaseError(err) => {
                 tracing::error!("Database error: {:?}", err);
@@ -24,6 +35,12 @@ impl IntoResponse for AppError {
             AppError::NotFound => (StatusCode::NOT_FOUND, "Resource not found"),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
+            AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden"),
+            AppError::EmailNotVerified => (StatusCode::FORBIDDEN, "Email address not verified"),
+            AppError::PasswordResetRequired => (StatusCode::FORBIDDEN, "Password reset required, check your email"),
+            AppError::NotWhileImpersonating => (StatusCode::FORBIDDEN, "Not allowed while impersonating a user"),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.as_str()),
+            AppError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, "Too many attempts, try again later"),
             AppError::InternalServerError(msg) => {
                 tracing::error!("Internal server error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
@@ -35,7 +52,12 @@ impl IntoResponse for AppError {
             "status": status.as_u16()
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(secs));
+        }
+
+        response
     }
 }
 
@@ -0,0 +1,209 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::api_keys::new_api_key;
+use crate::auth::{hash_token, CurrentUser};
+use crate::error::AppError;
+use crate::models::*;
+use crate::permissions::{scope_permission, API_KEY_SCOPES};
+use crate::utils::ApiResponse;
+
+// Known scopes without duplicates, in the order given
+fn validate_scopes(scopes: &[String]) -> Result<Vec<String>, AppError> {
+    if scopes.is_empty() {
+        return Err(AppError::BadRequest("At least one scope is required".to_string()));
+    }
+
+    let mut validated: Vec<String> = Vec::new();
+    for scope in scopes {
+        let scope = scope.trim();
+        if scope_permission(scope).is_none() {
+            let known: Vec<&str> = API_KEY_SCOPES.iter().map(|(name, _)| *name).collect();
+            return Err(AppError::BadRequest(format!(
+                "Unknown scope '{}'. Must be one of: {}",
+                scope,
+                known.join(", ")
+            )));
+        }
+
+        if !validated.iter().any(|existing| existing == scope) {
+            validated.push(scope.to_string());
+        }
+    }
+
+    Ok(validated)
+}
+
+pub async fn get_api_keys(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let api_keys = sqlx::query_as!(
+        ApiKey,
+        r#"
+        SELECT id, client_id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_by, created_at
+        FROM api_keys
+        WHERE client_id = $1
+        ORDER BY created_at DESC
+        "#,
+        client_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(api_keys)))
+}
+
+/// Creates a key for the client. The response is the only place the key appears.
+pub async fn create_api_key(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    if request.name.trim().is_empty() {
+        return Err(AppError::BadRequest("Name is required".to_string()));
+    }
+
+    let scopes = validate_scopes(&request.scopes)?;
+
+    if request.expires_at.flatten().is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(AppError::BadRequest("Expiry must be in the future".to_string()));
+    }
+
+    let client_exists = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM clients WHERE id = $1",
+        client_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if client_exists.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    let (prefix, key) = new_api_key();
+
+    let api_key = sqlx::query_as!(
+        ApiKey,
+        r#"
+        INSERT INTO api_keys (id, client_id, name, prefix, key_hash, scopes, expires_at, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id, client_id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_by, created_at
+        "#,
+        Uuid::new_v4(),
+        client_id,
+        request.name.trim(),
+        prefix,
+        hash_token(&key),
+        &scopes,
+        request.expires_at,
+        current_user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(CreatedApiKeyResponse { api_key, key }))))
+}
+
+pub async fn get_api_key(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((client_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let api_key = sqlx::query_as!(
+        ApiKey,
+        r#"
+        SELECT id, client_id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_by, created_at
+        FROM api_keys
+        WHERE id = $1 AND client_id = $2
+        "#,
+        id,
+        client_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(api_key)))
+}
+
+/// Renames a key or changes its scopes or expiry. Revoked keys cannot be changed.
+pub async fn update_api_key(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((client_id, id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateApiKeyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    if request.name.as_ref().is_some_and(|name| name.trim().is_empty()) {
+        return Err(AppError::BadRequest("Name cannot be empty".to_string()));
+    }
+
+    let scopes = request.scopes.as_deref().map(validate_scopes).transpose()?;
+
+    if request.expires_at.flatten().is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(AppError::BadRequest("Expiry must be in the future".to_string()));
+    }
+
+    let api_key = sqlx::query_as!(
+        ApiKey,
+        r#"
+        UPDATE api_keys
+        SET name = COALESCE($3, name),
+            scopes = COALESCE($4, scopes),
+            expires_at = CASE WHEN $5 THEN $6 ELSE expires_at END
+        WHERE id = $1 AND client_id = $2 AND revoked_at IS NULL
+        RETURNING id, client_id, name, prefix, scopes, expires_at, last_used_at, revoked_at, created_by, created_at
+        "#,
+        id,
+        client_id,
+        request.name.as_deref().map(str::trim),
+        scopes.as_deref(),
+        request.expires_at.is_some(),
+        request.expires_at.flatten()
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(api_key)))
+}
+
+/// Revokes a key; it stops working immediately and stays listed for auditing.
+pub async fn revoke_api_key(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((client_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let result = sqlx::query!(
+        "UPDATE api_keys SET revoked_at = COALESCE(revoked_at, NOW()) WHERE id = $1 AND client_id = $2",
+        id,
+        client_id
+    )
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"revoked": true}))))
+}
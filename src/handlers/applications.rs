@@ -0,0 +1,285 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::error::AppError;
+use crate::permissions::authorize_application;
+use crate::auth::CurrentUser;
+use crate::utils::ApiResponse;
+use super::offers::rescind_open_offers;
+use super::pipeline::sync_stage_with_status;
+
+async fn record_status_change(
+    conn: &mut PgConnection,
+    application_id: Uuid,
+    from_status: Option<ApplicationStatus>,
+    to_status: ApplicationStatus,
+    actor: &CurrentUser,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    // clock_timestamp() rather than NOW(), so several steps in one transaction keep their order
+    sqlx::query!(
+        r#"
+        INSERT INTO application_status_history
+            (id, application_id, from_status, to_status, changed_by, changed_by_email, reason, changed_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, clock_timestamp())
+        "#,
+        Uuid::new_v4(),
+        application_id,
+        from_status.map(|status| status.as_str()),
+        to_status.as_str(),
+        actor.id,
+        actor.email,
+        reason
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves an application to `next`, rejecting transitions the state machine does not allow,
+/// records the change in `application_status_history` and moves the card to a pipeline
+/// stage matching the new status. Rejecting or withdrawing rescinds its open offers. Runs on the caller's connection so it can take part in
+/// a wider transaction.
+pub(crate) async fn transition_application(
+    conn: &mut PgConnection,
+    application_id: Uuid,
+    next: ApplicationStatus,
+    actor: &CurrentUser,
+    reason: Option<&str>,
+) -> Result<Application, AppError> {
+    // Lock the row so concurrent updates cannot both pass the transition check
+    let current = sqlx::query_scalar!(
+        r#"SELECT status as "status: ApplicationStatus" FROM applications WHERE id = $1 FOR UPDATE"#,
+        application_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if !current.can_transition_to(next) {
+        return Err(AppError::BadRequest(format!(
+            "Cannot move application from {} to {}",
+            current, next
+        )));
+    }
+
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        UPDATE applications
+        SET status = $1, updated_at = NOW()
+        WHERE id = $2
+        RETURNING id, job_id, worker_id, status as "status: ApplicationStatus", stage_id, stage_entered_at,
+                  cover_letter, applied_at, updated_at
+        "#,
+        next.as_str(),
+        application_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_status_change(&mut *conn, application_id, Some(current), next, actor, reason).await?;
+
+    if matches!(next, ApplicationStatus::Rejected | ApplicationStatus::Withdrawn) {
+        rescind_open_offers(&mut *conn, application_id, actor, &format!("Application {}", next)).await?;
+    }
+
+    // Open statuses keep the card on a matching board stage
+    if !next.is_terminal() {
+        return sync_stage_with_status(&mut *conn, application).await;
+    }
+
+    // Closed applications land on the job's matching terminal stage, if it defines one
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        UPDATE applications a
+        SET stage_id = s.id, stage_entered_at = NOW(),
+            stage_position = (SELECT COALESCE(MAX(stage_position) + 1, 0) FROM applications WHERE stage_id = s.id)
+        FROM (
+            SELECT id FROM job_pipeline_stages
+            WHERE job_id = $2 AND is_terminal = true AND is_success = $3
+            ORDER BY position
+            LIMIT 1
+        ) s
+        WHERE a.id = $1
+        RETURNING a.id, a.job_id, a.worker_id, a.status as "status: ApplicationStatus", a.stage_id, a.stage_entered_at,
+                  a.cover_letter, a.applied_at, a.updated_at
+        "#,
+        application_id,
+        application.job_id,
+        next == ApplicationStatus::Hired
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .unwrap_or(application);
+
+    Ok(application)
+}
+
+pub async fn create_application(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<CreateApplicationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let worker_id = current_user.linked_worker_id()?;
+
+    // Check if job exists and is still open
+    let is_active = sqlx::query_scalar!(
+        "SELECT is_active FROM job_postings WHERE id = $1",
+        job_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if !is_active.unwrap_or(false) {
+        return Err(AppError::BadRequest("Job is no longer accepting applications".to_string()));
+    }
+
+    let cover_letter = request.cover_letter
+        .map(|letter| letter.trim().to_string())
+        .filter(|letter| !letter.is_empty());
+
+    let mut tx = pool.begin().await?;
+
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        WITH entry AS (
+            SELECT id FROM job_pipeline_stages WHERE job_id = $2 ORDER BY position LIMIT 1
+        )
+        INSERT INTO applications (id, job_id, worker_id, status, cover_letter, stage_id, stage_position)
+        VALUES ($1, $2, $3, $4, $5,
+                (SELECT id FROM entry),
+                (SELECT COALESCE(MAX(stage_position) + 1, 0) FROM applications WHERE stage_id = (SELECT id FROM entry)))
+        RETURNING id, job_id, worker_id, status as "status: ApplicationStatus", stage_id, stage_entered_at,
+                  cover_letter, applied_at, updated_at
+        "#,
+        Uuid::new_v4(),
+        job_id,
+        worker_id,
+        ApplicationStatus::Pending.as_str(),
+        cover_letter
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        // UNIQUE(job_id, worker_id) - the worker already applied to this job
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::Conflict("You have already applied to this job".to_string())
+        }
+        e => AppError::from(e),
+    })?;
+
+    record_status_change(&mut tx, application.id, None, application.status, &current_user, None).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(application))))
+}
+
+pub async fn withdraw_application(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let worker_id = current_user.linked_worker_id()?;
+
+    // Applications of other workers are reported as missing
+    let owned = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM applications WHERE id = $1 AND worker_id = $2",
+        id,
+        worker_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if owned.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    let mut tx = pool.begin().await?;
+    let application = transition_application(
+        &mut tx,
+        id,
+        ApplicationStatus::Withdrawn,
+        &current_user,
+        Some("Withdrawn by candidate"),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(application)))
+}
+
+pub async fn update_application_status(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateApplicationStatusRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_application(&pool, &current_user, id).await?;
+
+    let reason = request.reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty());
+
+    // Compliance needs to know why a candidate was turned down
+    if request.status == ApplicationStatus::Rejected && reason.is_none() {
+        return Err(AppError::BadRequest("A reason is required when rejecting an application".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+    let application = transition_application(&mut tx, id, request.status, &current_user, reason).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(application)))
+}
+
+pub async fn get_application_history(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_application(&pool, &current_user, id).await?;
+
+    let existing = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM applications WHERE id = $1",
+        id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if existing.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    let history = sqlx::query_as!(
+        ApplicationStatusHistory,
+        r#"
+        SELECT id, application_id,
+               from_status as "from_status: ApplicationStatus",
+               to_status as "to_status: ApplicationStatus",
+               changed_by, changed_by_email, reason, changed_at
+        FROM application_status_history
+        WHERE application_id = $1
+        ORDER BY changed_at ASC
+        "#,
+        id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(history)))
+}
@@ -1,19 +1,28 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{ConnectInfo, Path, State},
+    Extension,
+    http::{header, StatusCode},
     response::IntoResponse,
     Json,
 };
-use crate::auth::{AuthService, CurrentUser};
+use crate::auth::{hash_token, invalidate_sessions, AuthService, Claims, CurrentUser, SessionDevice};
 use crate::models::*;
 use crate::error::AppError;
-use crate::utils::{ApiResponse, validate_email};
+use crate::utils::{ApiResponse, app_base_url, validate_email};
+use crate::throttle::LoginThrottle;
+use crate::mailer::{deliver, Email, Mailer};
+use crate::action_tokens::{consume_action_token, issue_action_token, issue_unless_recent, TokenPurpose};
 use sqlx::PgPool;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
 use chrono;
 
 pub async fn register(
     State(auth_service): State<AuthService>,
     State(pool): State<PgPool>,
+    State(mailer): State<Arc<dyn Mailer>>,
     Json(request): Json<CreateUserRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate input
@@ -25,16 +34,7 @@ pub async fn register(
         return Err(AppError::BadRequest("Invalid email format".to_string()));
     }
 
-    if request.password.len() < 8 {
-        return Err(AppError::BadRequest("Password must be at least 8 characters".to_string()));
-    }
-
-    // Password complexity validation
-    if !is_strong_password(&request.password) {
-        return Err(AppError::BadRequest(
-            "Password must contain at least one uppercase letter, one lowercase letter, one number, and one special character".to_string()
-        ));
-    }
+    auth_service.password_policy().validate(&request.password)?;
 
     let valid_roles = ["admin", "client", "worker"];
     if !valid_roles.contains(&request.role.as_str()) {
@@ -56,13 +56,113 @@ pub async fn register(
         _ => {} // Admin doesn't need specific IDs
     }
 
+    // Registration is public, so nobody may claim an account they don't own
+    match request.role.as_str() {
+        // Admins never sign up: the first comes from `create-admin`, later ones are promoted by an admin
+        "admin" => return Err(AppError::Forbidden),
+        "client" => {
+            let profile = sqlx::query!(
+                r#"
+                SELECT p.email, (SELECT COUNT(*) FROM users u WHERE u.client_id = p.id) as "accounts!"
+                FROM clients p
+                WHERE p.id = $1
+                "#,
+                request.client_id
+            )
+            .fetch_optional(&pool)
+            .await?
+            .ok_or(AppError::BadRequest("Client not found".to_string()))?;
+
+            ensure_profile_claimable(&request.email, &profile.email, profile.accounts)?;
+        },
+        _ => {
+            let profile = sqlx::query!(
+                r#"
+                SELECT p.email, (SELECT COUNT(*) FROM users u WHERE u.worker_id = p.id) as "accounts!"
+                FROM workers p
+                WHERE p.id = $1
+                "#,
+                request.worker_id
+            )
+            .fetch_optional(&pool)
+            .await?
+            .ok_or(AppError::BadRequest("Worker not found".to_string()))?;
+
+            ensure_profile_claimable(&request.email, &profile.email, profile.accounts)?;
+        },
+    }
+
     let user = auth_service.register_user(&pool, request).await?;
+
+    let mut conn = pool.acquire().await?;
+    let token = issue_action_token(&mut conn, user.id, TokenPurpose::EmailVerification).await?;
+    send_verification_email(mailer, &user.email, &token).await;
+
     Ok((StatusCode::CREATED, Json(ApiResponse::success(user))))
 }
 
+/// Marks the email of the token's account as verified.
+pub async fn verify_email(
+    State(pool): State<PgPool>,
+    Json(request): Json<VerifyEmailRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user_id = consume_action_token(&mut tx, request.token.trim(), TokenPurpose::EmailVerification)
+        .await?
+        .ok_or(AppError::BadRequest("Verification link is invalid or has expired".to_string()))?;
+
+    sqlx::query!(
+        "UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW() WHERE id = $1",
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Email address verified"
+    }))))
+}
+
+/// Sends a new verification link, voiding the previous one. Like the password
+/// reset request, the answer never reveals whether the account exists.
+pub async fn resend_verification_email(
+    State(pool): State<PgPool>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Json(request): Json<ResendVerificationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !validate_email(&request.email) {
+        return Err(AppError::BadRequest("Invalid email format".to_string()));
+    }
+
+    let user = sqlx::query!(
+        "SELECT id, email FROM users WHERE LOWER(email) = LOWER($1) AND is_active = true AND email_verified_at IS NULL",
+        request.email.trim()
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    if let Some(user) = user {
+        let mut conn = pool.acquire().await?;
+
+        if let Some(token) = issue_unless_recent(&mut conn, user.id, TokenPurpose::EmailVerification).await? {
+            send_verification_email(mailer, &user.email, &token).await;
+        }
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "If an unverified account exists for this email, a verification link has been sent"
+    }))))
+}
+
 pub async fn login(
     State(auth_service): State<AuthService>,
     State(pool): State<PgPool>,
+    State(throttle): State<LoginThrottle>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    device: SessionDevice,
     Json(request): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     // Validate input
@@ -78,9 +178,33 @@ pub async fn login(
         return Err(AppError::BadRequest("Password is required".to_string()));
     }
 
-    // Rate limiting could be added here
-    let response = auth_service.authenticate_user(&pool, request).await?;
-    Ok(Json(ApiResponse::success(response)))
+    // Checked before hashing so blocked callers cost nothing
+    let ip = addr.ip();
+    if let Err(wait) = throttle.check(&request.email, ip, Instant::now()) {
+        return Err(AppError::TooManyRequests(wait.as_secs_f64().ceil() as u64));
+    }
+
+    let email = request.email.clone();
+    match auth_service.authenticate_user(&pool, request, &device).await {
+        Ok(response) => {
+            throttle.record_success(&email);
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(AppError::Unauthorized) => {
+            throttle.record_failure(&email, ip, Instant::now());
+            Err(AppError::Unauthorized)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Public keys that verify our access tokens, in the standard JWK Set format
+/// (not wrapped in `ApiResponse`) so other services can consume it directly.
+pub async fn get_jwks(State(auth_service): State<AuthService>) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(auth_service.jwks()),
+    )
 }
 
 pub async fn get_current_user(
@@ -98,8 +222,11 @@ pub async fn get_current_user(
         "client_id": user.client_id,
         "worker_id": user.worker_id,
         "is_active": user.is_active,
+        "email_verified_at": user.email_verified_at,
+        "last_login_at": user.last_login_at,
         "created_at": user.created_at,
-        "updated_at": user.updated_at
+        "updated_at": user.updated_at,
+        "impersonated_by": current_user.impersonated_by
     });
     
     Ok(Json(ApiResponse::success(safe_user)))
@@ -108,9 +235,12 @@ pub async fn get_current_user(
 pub async fn update_password(
     State(auth_service): State<AuthService>,
     State(pool): State<PgPool>,
-    CurrentUser(current_user): CurrentUser,
+    current_user: CurrentUser,
+    device: SessionDevice,
     Json(request): Json<serde_json::Value>,
 ) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
     let current_password = request.get("current_password")
         .and_then(|p| p.as_str())
         .ok_or(AppError::BadRequest("Current password is required".to_string()))?;
@@ -124,15 +254,7 @@ pub async fn update_password(
         .ok_or(AppError::BadRequest("Password confirmation is required".to_string()))?;
 
     // Validate new password
-    if new_password.len() < 8 {
-        return Err(AppError::BadRequest("New password must be at least 8 characters".to_string()));
-    }
-
-    if !is_strong_password(new_password) {
-        return Err(AppError::BadRequest(
-            "Password must contain at least one uppercase letter, one lowercase letter, one number, and one special character".to_string()
-        ));
-    }
+    auth_service.password_policy().validate(new_password)?;
 
     if new_password != confirm_password {
         return Err(AppError::BadRequest("New password and confirmation do not match".to_string()));
@@ -153,71 +275,382 @@ pub async fn update_password(
     .ok_or(AppError::NotFound)?;
 
     // Verify current password
-    let is_valid = auth_service.verify_password(current_password, &user.password_hash)?;
+    let is_valid = auth_service.verify_password(current_password, &user.password_hash).await?;
     if !is_valid {
         return Err(AppError::BadRequest("Current password is incorrect".to_string()));
     }
 
     // Hash new password
-    let new_password_hash = auth_service.hash_password(new_password)?;
+    let new_password_hash = auth_service.hash_password(new_password).await?;
+
+    // Update password and end every session that used the old one
+    let mut tx = pool.begin().await?;
 
-    // Update password
     sqlx::query!(
-        "UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2",
+        "UPDATE users SET password_hash = $1, password_reset_required = false, updated_at = NOW() WHERE id = $2",
         new_password_hash,
         current_user.id
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await?;
 
+    invalidate_sessions(&mut tx, current_user.id).await?;
+
+    tx.commit().await?;
+
+    // Keep the caller signed in with a fresh session
+    let user = auth_service.get_user_by_id(&pool, current_user.id).await?;
+    let session = auth_service.issue_session(&pool, user, &device).await?;
+
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": "Password updated successfully",
-        "timestamp": chrono::Utc::now()
+        "timestamp": chrono::Utc::now(),
+        "token": session.token,
+        "refresh_token": session.refresh_token,
+        "expires_in": session.expires_in
     }))))
 }
 
+/// Ends the caller's session, revoking the access token used for the call and the
+/// presented refresh token's family.
 pub async fn logout(
-    State(_auth_service): State<AuthService>,
-    CurrentUser(_current_user): CurrentUser,
-) -> Result<impl IntoResponse, AppError> {
-    // In a stateless JWT system, logout is typically handled client-side
-    // by removing the token. For server-side logout, you could:
-    // 1. Add the token to a blacklist
-    // 2. Use short-lived tokens with refresh tokens
-    // 3. Maintain a session store
-    
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Extension(claims): Extension<Claims>,
+    request: Option<Json<LogoutRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(session_id) = current_user.session_id {
+        auth_service.revoke_session(&pool, current_user.id, session_id).await?;
+    }
+
+    // The body is optional: a bare POST logs out the session of the access token
+    if let Some(refresh_token) = request.and_then(|Json(request)| request.refresh_token) {
+        auth_service.revoke_refresh_token(&pool, current_user.id, &refresh_token).await?;
+    }
+
+    auth_service.revoke_access_token(&pool, &claims).await?;
+
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "message": "Logged out successfully",
-        "instruction": "Please remove the token from client storage"
+        "message": "Logged out successfully"
     }))))
 }
 
-pub async fn refresh_token(
+/// Ends every session of the caller on every device, including this one.
+pub async fn logout_everywhere(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let mut conn = pool.acquire().await?;
+    invalidate_sessions(&mut conn, current_user.id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Logged out of all sessions"
+    }))))
+}
+
+/// Devices the caller is signed in on; `current` marks the one making the request.
+pub async fn get_sessions(
     State(auth_service): State<AuthService>,
     State(pool): State<PgPool>,
-    CurrentUser(current_user): CurrentUser,
+    current_user: CurrentUser,
 ) -> Result<impl IntoResponse, AppError> {
-    let user = auth_service.get_user_by_id(&pool, current_user.id).await?;
-    
-    // Generate new token
-    let new_token = auth_service.generate_token(&user)?;
-    
+    let sessions = auth_service
+        .list_sessions(&pool, current_user.id, current_user.session_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(sessions)))
+}
+
+/// Signs the caller out on one device.
+pub async fn revoke_session(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(session_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    auth_service.revoke_session(&pool, current_user.id, session_id).await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"revoked": true}))))
+}
+
+/// Emails a one-time sign-in link to a worker. The answer is the same for every
+/// address, so it reveals neither accounts nor roles.
+pub async fn request_magic_link(
+    State(pool): State<PgPool>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Json(request): Json<MagicLinkRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !validate_email(&request.email) {
+        return Err(AppError::BadRequest("Invalid email format".to_string()));
+    }
+
+    // Candidates only; accounts with access to other people's data keep their password
+    let user = sqlx::query!(
+        "SELECT id, email FROM users WHERE LOWER(email) = LOWER($1) AND is_active = true AND role = 'worker'",
+        request.email.trim()
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    if let Some(user) = user {
+        let mut tx = pool.begin().await?;
+
+        if let Some(token) = issue_unless_recent(&mut tx, user.id, TokenPurpose::MagicLink).await? {
+            tx.commit().await?;
+
+            let email = Email {
+                to: user.email,
+                subject: "Your sign-in link".to_string(),
+                body: format!(
+                    "Open this link within {} minutes to sign in. It works once:\n{}/magic-link?token={}\n\n\
+                     If you didn't ask for it, ignore this email.",
+                    TokenPurpose::MagicLink.ttl().num_minutes(),
+                    app_base_url(),
+                    token
+                ),
+            };
+
+            if let Err(e) = deliver(mailer, email).await {
+                tracing::error!("Failed to send magic link email: {:?}", e);
+            }
+        }
+    }
+
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "token": new_token,
-        "user": {
-            "id": user.id,
-            "email": user.email,
-            "role": user.role
+        "message": "If a candidate account exists for this email, a sign-in link has been sent"
+    }))))
+}
+
+/// Exchanges a magic link token for the same answer as `login`: a session, or
+/// an MFA challenge. Opening the link also proves the email address.
+pub async fn login_with_magic_link(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    State(throttle): State<LoginThrottle>,
+    device: SessionDevice,
+    Json(request): Json<MagicLinkLoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user_id = consume_action_token(&mut tx, request.token.trim(), TokenPurpose::MagicLink)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW())
+        WHERE id = $1 AND is_active = true AND role = 'worker'
+        RETURNING *
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::Unauthorized)?;
+
+    tx.commit().await?;
+    throttle.record_success(&user.email);
+
+    let response = auth_service.complete_first_factor(&pool, user, &device).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Emails a password reset link. The answer is the same whether or not the email
+/// has an account, so the endpoint cannot be used to discover accounts.
+pub async fn request_password_reset(
+    State(pool): State<PgPool>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Json(request): Json<PasswordResetRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !validate_email(&request.email) {
+        return Err(AppError::BadRequest("Invalid email format".to_string()));
+    }
+
+    let user = sqlx::query!(
+        "SELECT id, email FROM users WHERE LOWER(email) = LOWER($1) AND is_active = true",
+        request.email.trim()
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    if let Some(user) = user {
+        let mut tx = pool.begin().await?;
+
+        if let Some(token) = issue_unless_recent(&mut tx, user.id, TokenPurpose::PasswordReset).await? {
+            tx.commit().await?;
+
+            let email = Email {
+                to: user.email,
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Someone asked to reset the password for your account.\n\n\
+                     Use this link within {} minutes to choose a new password:\n{}/reset-password?token={}\n\n\
+                     If it wasn't you, ignore this email; your password stays the same.",
+                    TokenPurpose::PasswordReset.ttl().num_minutes(),
+                    app_base_url(),
+                    token
+                ),
+            };
+
+            // Reported in the logs only, the response must not differ
+            if let Err(e) = deliver(mailer, email).await {
+                tracing::error!("Failed to send password reset email: {:?}", e);
+            }
         }
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "If an account exists for this email, a password reset link has been sent"
     }))))
 }
 
-// Helper function to validate password strength
-fn is_strong_password(password: &str) -> bool {
-    let has_uppercase = password.chars().any(|c| c.is_uppercase());
-    let has_lowercase = password.chars().any(|c| c.is_lowercase());
-    let has_digit = password.chars().any(|c| c.is_ascii_digit());
-    let has_special = password.chars().any(|c| "!@#$%^&*()_+-=[]{}|;:,.<>?".contains(c));
-    
-    has_uppercase && has_lowercase && has_digit && has_special
-}
\ No newline at end of file
+/// Sets a new password with a token from a reset email. The token works once,
+/// and every existing session of the user is ended.
+pub async fn confirm_password_reset(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    State(throttle): State<LoginThrottle>,
+    Json(request): Json<ConfirmPasswordResetRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_service.password_policy().validate(&request.new_password)?;
+
+    if request.new_password != request.confirm_password {
+        return Err(AppError::BadRequest("New password and confirmation do not match".to_string()));
+    }
+
+    let invalid_link = || AppError::BadRequest("Password reset link is invalid or has expired".to_string());
+    let new_password_hash = auth_service.hash_password(&request.new_password).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let user_id = consume_action_token(&mut tx, request.token.trim(), TokenPurpose::PasswordReset)
+        .await?
+        .ok_or_else(invalid_link)?;
+
+    let email = sqlx::query_scalar!(
+        r#"
+        UPDATE users SET password_hash = $1, password_reset_required = false, updated_at = NOW()
+        WHERE id = $2 AND is_active = true
+        RETURNING email
+        "#,
+        new_password_hash,
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(invalid_link)?;
+
+    invalidate_sessions(&mut tx, user_id).await?;
+
+    tx.commit().await?;
+
+    // Whoever holds the mailbox may sign in again right away
+    throttle.unlock_account(&email);
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Password has been reset, please log in with your new password"
+    }))))
+}
+
+/// Creates the account an invitation was sent for and signs it in. The
+/// invitation works once; the email counts as verified since the link was sent there.
+pub async fn accept_invitation(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    device: SessionDevice,
+    Json(request): Json<AcceptInvitationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    auth_service.password_policy().validate(&request.password)?;
+
+    let password_hash = auth_service.hash_password(&request.password).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let invitation = sqlx::query!(
+        r#"
+        UPDATE client_invitations SET accepted_at = NOW()
+        WHERE token_hash = $1 AND accepted_at IS NULL AND expires_at > NOW()
+        RETURNING client_id, email, team_role
+        "#,
+        hash_token(request.token.trim())
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Invitation link is invalid or has expired".to_string()))?;
+
+    // The unique email constraint decides, so two concurrent sign-ups can't both pass
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        INSERT INTO users (id, email, password_hash, role, client_id, team_role, email_verified_at)
+        VALUES ($1, $2, $3, 'client', $4, $5, NOW())
+        ON CONFLICT (email) DO NOTHING
+        RETURNING *
+        "#,
+        Uuid::new_v4(),
+        invitation.email,
+        password_hash,
+        invitation.client_id,
+        invitation.team_role
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict("A user with this email already exists".to_string()))?;
+
+    tx.commit().await?;
+
+    let response = auth_service.complete_first_factor(&pool, user, &device).await?;
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+}
+
+/// Exchanges a refresh token for a new token pair. The presented token is used up.
+pub async fn refresh_token(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    Json(request): Json<RefreshTokenRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if request.refresh_token.trim().is_empty() {
+        return Err(AppError::BadRequest("Refresh token is required".to_string()));
+    }
+
+    let response = auth_service.rotate_refresh_token(&pool, request.refresh_token.trim()).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+// Delivery failures are only logged; the user can ask for another link
+async fn send_verification_email(mailer: Arc<dyn Mailer>, to: &str, token: &str) {
+    let email = Email {
+        to: to.to_string(),
+        subject: "Verify your email address".to_string(),
+        body: format!(
+            "Welcome! Please confirm this is your email address by opening this link within {} hours:\n{}/verify-email?token={}\n\n\
+             If you didn't create an account, ignore this email.",
+            TokenPurpose::EmailVerification.ttl().num_hours(),
+            app_base_url(),
+            token
+        ),
+    };
+
+    if let Err(e) = deliver(mailer, email).await {
+        tracing::error!("Failed to send verification email: {:?}", e);
+    }
+}
+
+// A client or worker profile can be claimed once, with the email it was created with
+fn ensure_profile_claimable(email: &str, profile_email: &str, accounts: i64) -> Result<(), AppError> {
+    if !email.trim().eq_ignore_ascii_case(profile_email.trim()) {
+        return Err(AppError::Forbidden);
+    }
+
+    if accounts > 0 {
+        return Err(AppError::Conflict("This profile already has an account".to_string()));
+    }
+
+    Ok(())
+}
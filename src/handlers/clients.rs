@@ -10,6 +10,7 @@ use uuid::Uuid;
 
 use crate::models::*;
 use crate::error::AppError;
+use crate::auth::CurrentUser;
 use crate::utils::{ApiResponse, PaginationParams, PaginatedResponse, validate_email, validate_phone};
 
 #[derive(Debug, Deserialize)]
@@ -73,8 +74,11 @@ pub async fn get_clients(
 
 pub async fn get_client(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(id)?;
+    
     let client = sqlx::query_as!(
         Client,
         r#"
@@ -234,6 +238,18 @@ pub async fn delete_client(
         return Err(AppError::BadRequest("Cannot delete client with active job postings".to_string()));
     }
     
+    // Placements are billing records and must outlive the client
+    let placements = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM placements WHERE client_id = $1",
+        id
+    )
+    .fetch_one(&pool)
+    .await?;
+    
+    if placements.unwrap_or(0) > 0 {
+        return Err(AppError::BadRequest("Cannot delete client with placements".to_string()));
+    }
+    
     let result = sqlx::query!(
         "DELETE FROM clients WHERE id = $1",
         id
@@ -0,0 +1,261 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Datelike, Duration, Utc};
+use serde::Deserialize;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::error::AppError;
+use crate::auth::CurrentUser;
+use crate::utils::{ApiResponse, PaginationParams, PaginatedResponse};
+
+#[derive(Debug, Deserialize)]
+pub struct InvoiceFilterQuery {
+    pub client_id: Option<Uuid>,
+    pub status: Option<InvoiceStatus>,
+}
+
+async fn fetch_line_items(conn: &mut PgConnection, invoice_id: Uuid) -> Result<Vec<InvoiceLineItem>, AppError> {
+    let line_items = sqlx::query_as!(
+        InvoiceLineItem,
+        r#"
+        SELECT id, invoice_id, position, description, quantity, unit_amount, amount
+        FROM invoice_line_items
+        WHERE invoice_id = $1
+        ORDER BY position ASC
+        "#,
+        invoice_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(line_items)
+}
+
+/// Issues the agency fee invoice for a placement using the client's fee agreement.
+/// Invoice numbers are sequential across years: INV-<year>-<sequence>.
+pub async fn generate_invoice(
+    State(pool): State<PgPool>,
+    Path(placement_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let placement = sqlx::query!(
+        r#"
+        SELECT p.id, p.client_id, p.start_date, p.agreed_salary, p.currency,
+               w.name as worker_name, j.title as job_title
+        FROM placements p
+        JOIN workers w ON p.worker_id = w.id
+        JOIN job_postings j ON p.job_id = j.id
+        WHERE p.id = $1
+        FOR UPDATE OF p
+        "#,
+        placement_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let already_invoiced = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM invoices WHERE placement_id = $1",
+        placement_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if already_invoiced.unwrap_or(0) > 0 {
+        return Err(AppError::Conflict("This placement has already been invoiced".to_string()));
+    }
+
+    let agreement = sqlx::query_as!(
+        FeeAgreement,
+        r#"
+        SELECT id, client_id, fee_type as "fee_type: FeeType", percentage_bps, flat_fee, currency,
+               payment_terms_days, created_at, updated_at
+        FROM fee_agreements
+        WHERE client_id = $1
+        "#,
+        placement.client_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::BadRequest("Client has no fee agreement".to_string()))?;
+
+    let fee = agreement.fee_for_salary(placement.agreed_salary)?;
+
+    let (currency, description) = match agreement.fee_type {
+        FeeType::Percentage => (
+            placement.currency.clone(),
+            format!(
+                "Placement fee: {} as {} ({}.{:02}% of {} {} first-year salary)",
+                placement.worker_name,
+                placement.job_title,
+                agreement.percentage_bps.unwrap_or(0) / 100,
+                agreement.percentage_bps.unwrap_or(0) % 100,
+                placement.agreed_salary,
+                placement.currency
+            ),
+        ),
+        FeeType::Flat => (
+            agreement.currency.clone(),
+            format!("Placement fee: {} as {} (flat fee)", placement.worker_name, placement.job_title),
+        ),
+    };
+
+    let issue_date = Utc::now().date_naive();
+    let due_date = issue_date + Duration::days(agreement.payment_terms_days as i64);
+
+    let sequence = sqlx::query_scalar!("SELECT nextval('invoice_number_seq')")
+        .fetch_one(&mut *tx)
+        .await?
+        .unwrap_or(0);
+    let invoice_number = format!("INV-{}-{:06}", issue_date.year(), sequence);
+
+    let invoice = sqlx::query_as!(
+        Invoice,
+        r#"
+        INSERT INTO invoices (id, invoice_number, client_id, placement_id, issue_date, due_date, currency, subtotal, total, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, invoice_number, client_id, placement_id, issue_date, due_date, currency, subtotal, total,
+                  status as "status: InvoiceStatus", paid_at, created_at, updated_at
+        "#,
+        Uuid::new_v4(),
+        invoice_number,
+        placement.client_id,
+        placement.id,
+        issue_date,
+        due_date,
+        currency,
+        fee,
+        fee,
+        InvoiceStatus::Unpaid.as_str()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO invoice_line_items (id, invoice_id, position, description, quantity, unit_amount, amount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        Uuid::new_v4(),
+        invoice.id,
+        0,
+        description,
+        1,
+        fee,
+        fee
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let line_items = fetch_line_items(&mut tx, invoice.id).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(InvoiceWithLineItems { invoice, line_items }))))
+}
+
+pub async fn get_invoices(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Query(pagination): Query<PaginationParams>,
+    Query(filters): Query<InvoiceFilterQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let client_id = current_user.client_filter(filters.client_id)?;
+    let pagination = PaginationParams::new(pagination.page, pagination.limit);
+    let status = filters.status.map(|status| status.as_str());
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM invoices
+        WHERE ($1::uuid IS NULL OR client_id = $1) AND ($2::varchar IS NULL OR status = $2)
+        "#,
+        client_id,
+        status
+    )
+    .fetch_one(&pool)
+    .await?
+    .unwrap_or(0);
+
+    let invoices = sqlx::query_as!(
+        Invoice,
+        r#"
+        SELECT id, invoice_number, client_id, placement_id, issue_date, due_date, currency, subtotal, total,
+               status as "status: InvoiceStatus", paid_at, created_at, updated_at
+        FROM invoices
+        WHERE ($1::uuid IS NULL OR client_id = $1) AND ($2::varchar IS NULL OR status = $2)
+        ORDER BY issue_date DESC, invoice_number DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        client_id,
+        status,
+        pagination.limit(),
+        pagination.offset()
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let response = PaginatedResponse::new(invoices, pagination.page.unwrap_or(1), pagination.limit.unwrap_or(20), total);
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+pub async fn get_invoice(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut conn = pool.acquire().await?;
+
+    let invoice = sqlx::query_as!(
+        Invoice,
+        r#"
+        SELECT id, invoice_number, client_id, placement_id, issue_date, due_date, currency, subtotal, total,
+               status as "status: InvoiceStatus", paid_at, created_at, updated_at
+        FROM invoices
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    current_user.authorize_client(invoice.client_id)?;
+
+    let line_items = fetch_line_items(&mut conn, invoice.id).await?;
+
+    Ok(Json(ApiResponse::success(InvoiceWithLineItems { invoice, line_items })))
+}
+
+pub async fn update_invoice_status(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateInvoiceStatusRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let invoice = sqlx::query_as!(
+        Invoice,
+        r#"
+        UPDATE invoices
+        SET status = $1,
+            paid_at = CASE WHEN $1 = 'paid' THEN COALESCE(paid_at, NOW()) ELSE NULL END,
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING id, invoice_number, client_id, placement_id, issue_date, due_date, currency, subtotal, total,
+                  status as "status: InvoiceStatus", paid_at, created_at, updated_at
+        "#,
+        request.status.as_str(),
+        id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(invoice)))
+}
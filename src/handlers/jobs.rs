@@ -10,7 +10,10 @@ use uuid::Uuid;
 
 use crate::models::*;
 use crate::error::AppError;
+use crate::auth::CurrentUser;
+use crate::permissions::{authorize_job, Role, TeamRole};
 use crate::utils::{ApiResponse, PaginationParams, PaginatedResponse};
+use super::pipeline::create_default_stages;
 
 #[derive(Debug, Deserialize)]
 pub struct JobFilterQuery {
@@ -23,16 +26,26 @@ pub struct JobFilterQuery {
     pub search: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PublicJobFilterQuery {
+    pub location: Option<String>,
+    pub job_type: Option<String>,
+    pub search: Option<String>,
+}
+
 pub async fn get_jobs(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Query(pagination): Query<PaginationParams>,
-    Query(filters): Query<JobFilterQuery>,
+    Query(mut filters): Query<JobFilterQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    filters.client_id = current_user.client_filter(filters.client_id)?;
+    
     let pagination = PaginationParams::new(pagination.page, pagination.limit);
     
     let mut base_query = "
         SELECT j.id, j.client_id, j.title, j.description, j.requirements, 
-               j.salary_range, j.location, j.job_type, j.is_active, j.created_at, j.updated_at,
+               j.salary_range, j.location, j.job_type, j.is_active, j.headcount, j.created_at, j.updated_at,
                c.company_name
         FROM job_postings j
         JOIN clients c ON j.client_id = c.id
@@ -53,6 +66,15 @@ pub async fn get_jobs(
         params.push(client_id.to_string());
     }
     
+    // Hiring managers only see the jobs assigned to them
+    if let Some(user_id) = current_user.assignment_filter() {
+        param_count += 1;
+        let assigned = format!(" AND j.id IN (SELECT job_id FROM job_assignments WHERE user_id = ${}::uuid)", param_count);
+        base_query.push_str(&assigned);
+        count_query.push_str(&assigned);
+        params.push(user_id.to_string());
+    }
+    
     if let Some(is_active) = filters.is_active {
         param_count += 1;
         base_query.push_str(&format!(" AND j.is_active = ${}", param_count));
@@ -127,6 +149,7 @@ pub async fn get_jobs(
             location: row.get("location"),
             job_type: row.get("job_type"),
             is_active: row.get("is_active"),
+            headcount: row.get("headcount"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
         };
@@ -138,14 +161,98 @@ pub async fn get_jobs(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// Active job postings for anonymous visitors
+pub async fn get_public_jobs(
+    State(pool): State<PgPool>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filters): Query<PublicJobFilterQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let pagination = PaginationParams::new(pagination.page, pagination.limit);
+    let location = filters.location.map(|location| format!("%{}%", location.trim()));
+    let search = filters.search.map(|search| format!("%{}%", search.trim()));
+    
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM job_postings
+        WHERE is_active = true
+          AND ($1::varchar IS NULL OR location ILIKE $1)
+          AND ($2::varchar IS NULL OR job_type = $2)
+          AND ($3::varchar IS NULL OR title ILIKE $3 OR description ILIKE $3)
+        "#,
+        location,
+        filters.job_type,
+        search
+    )
+    .fetch_one(&pool)
+    .await?
+    .unwrap_or(0);
+    
+    let jobs = sqlx::query_as!(
+        PublicJobPosting,
+        r#"
+        SELECT j.id, c.company_name, j.title, j.description, j.requirements, j.salary_range,
+               j.location, j.job_type, j.created_at
+        FROM job_postings j
+        JOIN clients c ON j.client_id = c.id
+        WHERE j.is_active = true
+          AND ($1::varchar IS NULL OR j.location ILIKE $1)
+          AND ($2::varchar IS NULL OR j.job_type = $2)
+          AND ($3::varchar IS NULL OR j.title ILIKE $3 OR j.description ILIKE $3)
+        ORDER BY j.created_at DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        location,
+        filters.job_type,
+        search,
+        pagination.limit(),
+        pagination.offset()
+    )
+    .fetch_all(&pool)
+    .await?;
+    
+    let response = PaginatedResponse::new(jobs, pagination.page.unwrap_or(1), pagination.limit.unwrap_or(20), total);
+    
+    Ok(Json(ApiResponse::success(response)))
+}
+
+pub async fn get_public_job(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = sqlx::query_as!(
+        PublicJobPosting,
+        r#"
+        SELECT j.id, c.company_name, j.title, j.description, j.requirements, j.salary_range,
+               j.location, j.job_type, j.created_at
+        FROM job_postings j
+        JOIN clients c ON j.client_id = c.id
+        WHERE j.id = $1 AND j.is_active = true
+        "#,
+        id
+    )
+    .fetch_optional(&pool)
+    .await?;
+    
+    match job {
+        Some(job) => Ok(Json(ApiResponse::success(job))),
+        None => Err(AppError::NotFound),
+    }
+}
+
 pub async fn get_job(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    // Workers browse every posting, clients only see their own
+    if current_user.role == Role::Client {
+        authorize_job(&pool, &current_user, id).await?;
+    }
+    
     let job = sqlx::query_as!(
         JobPosting,
         r#"
-        SELECT id, client_id, title, description, requirements, salary_range, location, job_type, is_active, created_at, updated_at
+        SELECT id, client_id, title, description, requirements, salary_range, location, job_type, is_active, headcount, created_at, updated_at
         FROM job_postings
         WHERE id = $1
         "#,
@@ -162,8 +269,11 @@ pub async fn get_job(
 
 pub async fn create_job(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Json(mut request): Json<CreateJobRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(request.client_id)?;
+    
     // Validate input
     if request.title.trim().is_empty() {
         return Err(AppError::BadRequest("Job title is required".to_string()));
@@ -177,6 +287,10 @@ pub async fn create_job(
         return Err(AppError::BadRequest("Job location is required".to_string()));
     }
     
+    if request.headcount.map_or(false, |headcount| headcount < 1) {
+        return Err(AppError::BadRequest("Headcount must be at least 1".to_string()));
+    }
+    
     // Check if client exists
     let client_exists = sqlx::query_scalar!(
         "SELECT COUNT(*) FROM clients WHERE id = $1",
@@ -193,12 +307,14 @@ pub async fn create_job(
     request.requirements.sort();
     request.requirements.dedup();
     
+    let mut tx = pool.begin().await?;
+    
     let job = sqlx::query_as!(
         JobPosting,
         r#"
-        INSERT INTO job_postings (id, client_id, title, description, requirements, salary_range, location, job_type, is_active)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-        RETURNING id, client_id, title, description, requirements, salary_range, location, job_type, is_active, created_at, updated_at
+        INSERT INTO job_postings (id, client_id, title, description, requirements, salary_range, location, job_type, is_active, headcount)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, client_id, title, description, requirements, salary_range, location, job_type, is_active, headcount, created_at, updated_at
         "#,
         Uuid::new_v4(),
         request.client_id,
@@ -208,19 +324,29 @@ pub async fn create_job(
         request.salary_range,
         request.location.trim(),
         request.job_type.unwrap_or_else(|| "full-time".to_string()),
-        request.is_active.unwrap_or(true)
+        request.is_active.unwrap_or(true),
+        request.headcount
     )
-    .fetch_one(&pool)
+    .fetch_one(&mut *tx)
     .await?;
     
+    // Every job starts with the default hiring pipeline
+    create_default_stages(&mut tx, job.id).await?;
+    
+    tx.commit().await?;
+    
     Ok((StatusCode::CREATED, Json(ApiResponse::success(job))))
 }
 
 pub async fn update_job(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
     Json(mut request): Json<CreateJobRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, id).await?;
+    current_user.authorize_client(request.client_id)?;
+    
     // Validate input
     if request.title.trim().is_empty() {
         return Err(AppError::BadRequest("Job title is required".to_string()));
@@ -234,6 +360,10 @@ pub async fn update_job(
         return Err(AppError::BadRequest("Job location is required".to_string()));
     }
     
+    if request.headcount.map_or(false, |headcount| headcount < 1) {
+        return Err(AppError::BadRequest("Headcount must be at least 1".to_string()));
+    }
+    
     // Check if job exists
     let existing = sqlx::query_scalar!(
         "SELECT COUNT(*) FROM job_postings WHERE id = $1",
@@ -267,9 +397,9 @@ pub async fn update_job(
         r#"
         UPDATE job_postings 
         SET client_id = $1, title = $2, description = $3, requirements = $4, salary_range = $5, 
-            location = $6, job_type = $7, is_active = $8, updated_at = NOW()
-        WHERE id = $9
-        RETURNING id, client_id, title, description, requirements, salary_range, location, job_type, is_active, created_at, updated_at
+            location = $6, job_type = $7, is_active = $8, headcount = $9, updated_at = NOW()
+        WHERE id = $10
+        RETURNING id, client_id, title, description, requirements, salary_range, location, job_type, is_active, headcount, created_at, updated_at
         "#,
         request.client_id,
         request.title.trim(),
@@ -279,6 +409,7 @@ pub async fn update_job(
         request.location.trim(),
         request.job_type.unwrap_or_else(|| "full-time".to_string()),
         request.is_active.unwrap_or(true),
+        request.headcount,
         id
     )
     .fetch_one(&pool)
@@ -289,8 +420,11 @@ pub async fn update_job(
 
 pub async fn delete_job(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, id).await?;
+    
     // Check if job exists
     let existing = sqlx::query_scalar!(
         "SELECT COUNT(*) FROM job_postings WHERE id = $1",
@@ -305,7 +439,7 @@ pub async fn delete_job(
     
     // Check if job has active applications
     let active_applications = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM applications WHERE job_id = $1 AND status IN ('pending', 'reviewing')",
+        "SELECT COUNT(*) FROM applications WHERE job_id = $1 AND status IN ('pending', 'reviewing', 'interview', 'offer')",
         id
     )
     .fetch_one(&pool)
@@ -315,6 +449,18 @@ pub async fn delete_job(
         return Err(AppError::BadRequest("Cannot delete job with active applications".to_string()));
     }
     
+    // Placements are billing records and must outlive the job
+    let placements = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM placements WHERE job_id = $1",
+        id
+    )
+    .fetch_one(&pool)
+    .await?;
+    
+    if placements.unwrap_or(0) > 0 {
+        return Err(AppError::BadRequest("Cannot delete job with placements".to_string()));
+    }
+    
     let result = sqlx::query!(
         "DELETE FROM job_postings WHERE id = $1",
         id
@@ -331,12 +477,16 @@ pub async fn delete_job(
 
 pub async fn get_job_applications(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(job_id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+    
     let applications = sqlx::query_as!(
         Application,
         r#"
-        SELECT a.id, a.job_id, a.worker_id, a.status, a.cover_letter, a.applied_at, a.updated_at,
+        SELECT a.id, a.job_id, a.worker_id, a.status as "status: ApplicationStatus",
+               a.stage_id, a.stage_entered_at, a.cover_letter, a.applied_at, a.updated_at,
                w.name as worker_name, w.email as worker_email
         FROM applications a
         JOIN workers w ON a.worker_id = w.id
@@ -350,3 +500,99 @@ pub async fn get_job_applications(
     
     Ok(Json(ApiResponse::success(applications)))
 }
+
+pub async fn get_job_assignments(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    let assignments = sqlx::query_as!(
+        JobAssignment,
+        r#"
+        SELECT ja.job_id, ja.user_id, u.email, ja.assigned_by, ja.created_at
+        FROM job_assignments ja
+        JOIN users u ON ja.user_id = u.id
+        WHERE ja.job_id = $1
+        ORDER BY ja.created_at ASC
+        "#,
+        job_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(assignments)))
+}
+
+/// Gives a hiring manager of the job's client access to the job.
+pub async fn assign_job(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<AssignJobRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    let assignee = sqlx::query!(
+        r#"
+        SELECT u.team_role
+        FROM users u
+        JOIN job_postings j ON j.client_id = u.client_id
+        WHERE u.id = $1 AND j.id = $2 AND u.role = 'client' AND u.is_active = true
+        "#,
+        request.user_id,
+        job_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("User is not an active member of the job's client".to_string()))?;
+
+    if assignee.team_role.as_deref().and_then(TeamRole::parse) != Some(TeamRole::HiringManager) {
+        return Err(AppError::BadRequest("Only hiring managers are assigned to jobs; other members see every job".to_string()));
+    }
+
+    let assignment = sqlx::query_as!(
+        JobAssignment,
+        r#"
+        WITH assigned AS (
+            INSERT INTO job_assignments (job_id, user_id, assigned_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (job_id, user_id) DO UPDATE SET job_id = EXCLUDED.job_id
+            RETURNING job_id, user_id, assigned_by, created_at
+        )
+        SELECT a.job_id, a.user_id, u.email, a.assigned_by, a.created_at
+        FROM assigned a
+        JOIN users u ON a.user_id = u.id
+        "#,
+        job_id,
+        request.user_id,
+        current_user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(assignment))))
+}
+
+pub async fn unassign_job(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((job_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    let result = sqlx::query!(
+        "DELETE FROM job_assignments WHERE job_id = $1 AND user_id = $2",
+        job_id,
+        user_id
+    )
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"deleted": true}))))
+}
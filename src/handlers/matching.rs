@@ -9,6 +9,8 @@ use uuid::Uuid;
 
 use crate::models::*;
 use crate::error::AppError;
+use crate::auth::CurrentUser;
+use crate::permissions::authorize_job;
 use crate::utils::{ApiResponse, calculate_skill_match_score, calculate_experience_score};
 
 #[derive(Debug, Deserialize)]
@@ -19,13 +21,16 @@ pub struct MatchQuery {
 
 pub async fn find_matches(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(job_id): Path<Uuid>,
     Query(query): Query<MatchQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
     let job = sqlx::query_as!(
         JobPosting,
         r#"
-        SELECT id, client_id, title, description, requirements, salary_range, location, job_type, is_active, created_at, updated_at
+        SELECT id, client_id, title, description, requirements, salary_range, location, job_type, is_active, headcount, created_at, updated_at
         FROM job_postings
         WHERE id = $1 AND is_active = true
         "#,
@@ -94,9 +99,12 @@ pub async fn find_matches(
 
 pub async fn find_jobs_for_worker(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(worker_id): Path<Uuid>,
     Query(query): Query<MatchQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_worker(worker_id)?;
+
     let worker = sqlx::query_as!(
         Worker,
         r#"
@@ -113,7 +121,7 @@ pub async fn find_jobs_for_worker(
     let jobs = sqlx::query_as!(
         JobPosting,
         r#"
-        SELECT id, client_id, title, description, requirements, salary_range, location, job_type, is_active, created_at, updated_at
+        SELECT id, client_id, title, description, requirements, salary_range, location, job_type, is_active, headcount, created_at, updated_at
         FROM job_postings
         WHERE is_active = true
         ORDER BY created_at DESC
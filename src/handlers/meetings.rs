@@ -11,6 +11,8 @@ use chrono::{DateTime, Utc};
 
 use crate::models::*;
 use crate::error::AppError;
+use crate::auth::CurrentUser;
+use crate::permissions::{authorize_meeting, authorize_meeting_job};
 use crate::utils::{ApiResponse, PaginationParams, PaginatedResponse};
 
 #[derive(Debug, Deserialize)]
@@ -24,9 +26,13 @@ pub struct MeetingFilterQuery {
 
 pub async fn get_meetings(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Query(pagination): Query<PaginationParams>,
-    Query(filters): Query<MeetingFilterQuery>,
+    Query(mut filters): Query<MeetingFilterQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    filters.client_id = current_user.client_filter(filters.client_id)?;
+    filters.worker_id = current_user.worker_filter(filters.worker_id)?;
+    
     let pagination = PaginationParams::new(pagination.page, pagination.limit);
     
     let mut base_query = "
@@ -61,6 +67,15 @@ pub async fn get_meetings(
         params.push(worker_id.to_string());
     }
     
+    // Hiring managers only see meetings about their assigned jobs
+    if let Some(user_id) = current_user.assignment_filter() {
+        param_count += 1;
+        let assigned = format!(" AND m.job_id IN (SELECT job_id FROM job_assignments WHERE user_id = ${}::uuid)", param_count);
+        base_query.push_str(&assigned);
+        count_query.push_str(&assigned);
+        params.push(user_id.to_string());
+    }
+    
     if let Some(status) = &filters.status {
         param_count += 1;
         base_query.push_str(&format!(" AND m.status = ${}", param_count));
@@ -127,8 +142,11 @@ pub async fn get_meetings(
 
 pub async fn get_meeting(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_meeting(&pool, &current_user, id).await?;
+    
     let meeting = sqlx::query_as!(
         Meeting,
         r#"
@@ -150,8 +168,12 @@ pub async fn get_meeting(
 
 pub async fn create_meeting(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Json(mut request): Json<CreateMeetingRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(request.client_id)?;
+    authorize_meeting_job(&pool, &current_user, request.job_id).await?;
+    
     // Validate input
     if request.title.trim().is_empty() {
         return Err(AppError::BadRequest("Meeting title is required".to_string()));
@@ -229,9 +251,15 @@ pub async fn create_meeting(
 
 pub async fn update_meeting(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
     Json(mut request): Json<CreateMeetingRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_meeting(&pool, &current_user, id).await?;
+    
+    current_user.authorize_client(request.client_id)?;
+    authorize_meeting_job(&pool, &current_user, request.job_id).await?;
+    
     // Validate input
     if request.title.trim().is_empty() {
         return Err(AppError::BadRequest("Meeting title is required".to_string()));
@@ -304,9 +332,12 @@ pub async fn update_meeting(
 
 pub async fn update_meeting_status(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
     Json(request): Json<serde_json::Value>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_meeting(&pool, &current_user, id).await?;
+    
     let status = request.get("status")
         .and_then(|s| s.as_str())
         .ok_or(AppError::BadRequest("Status is required".to_string()))?;
@@ -348,8 +379,11 @@ pub async fn update_meeting_status(
 
 pub async fn delete_meeting(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_meeting(&pool, &current_user, id).await?;
+    
     // Check if meeting exists
     let existing = sqlx::query_scalar!(
         "SELECT COUNT(*) FROM meetings WHERE id = $1",
@@ -378,7 +412,12 @@ pub async fn delete_meeting(
 
 pub async fn get_upcoming_meetings(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
 ) -> Result<impl IntoResponse, AppError> {
+    let client_id = current_user.client_filter(None)?;
+    let worker_id = current_user.worker_filter(None)?;
+    let assigned_to = current_user.assignment_filter();
+    
     let meetings = sqlx::query_as!(
         Meeting,
         r#"
@@ -386,9 +425,14 @@ pub async fn get_upcoming_meetings(
                duration_minutes, status, meeting_url, location, created_at, updated_at
         FROM meetings
         WHERE scheduled_at > NOW() AND status = 'scheduled'
+          AND ($1::uuid IS NULL OR client_id = $1) AND ($2::uuid IS NULL OR worker_id = $2)
+          AND ($3::uuid IS NULL OR job_id IN (SELECT job_id FROM job_assignments WHERE user_id = $3))
         ORDER BY scheduled_at ASC
         LIMIT 10
-        "#
+        "#,
+        client_id,
+        worker_id,
+        assigned_to
     )
     .fetch_all(&pool)
     .await?;
@@ -0,0 +1,270 @@
+use axum::{
+    extract::{ConnectInfo, Path, State},
+    response::IntoResponse,
+    Json,
+};
+use sqlx::PgPool;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use crate::auth::{AuthService, CurrentUser, SessionDevice};
+use crate::error::AppError;
+use crate::mfa;
+use crate::models::*;
+use crate::permissions::Role;
+use crate::throttle::LoginThrottle;
+use crate::totp;
+use crate::utils::ApiResponse;
+
+// Shown as the account's label in authenticator apps
+const TOTP_ISSUER: &str = "Recruitment Agency";
+
+/// Second login step: exchanges the MFA challenge and a code for a session. When
+/// the role requires MFA and the user was enrolling, the first code confirms the
+/// authenticator and the response carries the recovery codes.
+pub async fn verify_mfa(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    State(throttle): State<LoginThrottle>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    device: SessionDevice,
+    Json(request): Json<MfaVerifyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_service.verify_mfa_challenge(&request.mfa_token)?;
+
+    let user = sqlx::query_as!(
+        User,
+        "SELECT * FROM users WHERE id = $1 AND is_active = true",
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::Unauthorized)?;
+
+    // Codes are short, so wrong ones count against the login limits
+    let ip = addr.ip();
+    if let Err(wait) = throttle.check(&user.email, ip, Instant::now()) {
+        return Err(AppError::TooManyRequests(wait.as_secs_f64().ceil() as u64));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let settings = mfa::mfa_settings(&mut tx, user.id)
+        .await?
+        .ok_or(AppError::BadRequest("Set up an authenticator app first".to_string()))?;
+
+    let (valid, recovery_codes) = if settings.enabled {
+        (mfa::check_second_factor(&mut tx, user.id, &request.code).await?, None)
+    } else if mfa::check_totp(&mut tx, user.id, &request.code).await? {
+        (true, Some(mfa::enable_mfa(&mut tx, user.id).await?))
+    } else {
+        (false, None)
+    };
+
+    if !valid {
+        throttle.record_failure(&user.email, ip, Instant::now());
+        return Err(AppError::Unauthorized);
+    }
+
+    tx.commit().await?;
+    throttle.record_success(&user.email);
+
+    let session = auth_service.issue_session(&pool, user, &device).await?;
+    Ok(Json(ApiResponse::success(MfaSessionResponse { session, recovery_codes })))
+}
+
+/// Provisions an authenticator in the middle of a login whose role requires MFA.
+pub async fn setup_mfa_challenge(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    Json(request): Json<MfaChallengeSetupRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let user_id = auth_service.verify_mfa_challenge(&request.mfa_token)?;
+
+    let email = sqlx::query_scalar!(
+        "SELECT email FROM users WHERE id = $1 AND is_active = true",
+        user_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::Unauthorized)?;
+
+    let mut conn = pool.acquire().await?;
+    let secret = mfa::start_enrollment(&mut conn, user_id).await?;
+
+    Ok(Json(ApiResponse::success(MfaSetupResponse {
+        provisioning_uri: totp::provisioning_uri(&secret, &email, TOTP_ISSUER),
+        secret,
+    })))
+}
+
+pub async fn get_mfa_status(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    let mut conn = pool.acquire().await?;
+
+    let enabled = mfa::mfa_settings(&mut conn, current_user.id)
+        .await?
+        .is_some_and(|settings| settings.enabled);
+    let required_by_role = mfa::role_requires_mfa(&mut conn, current_user.role.as_str()).await?;
+    let recovery_codes_remaining = mfa::remaining_recovery_codes(&mut conn, current_user.id).await?;
+
+    Ok(Json(ApiResponse::success(MfaStatusResponse {
+        enabled,
+        required_by_role,
+        recovery_codes_remaining,
+    })))
+}
+
+/// Starts enrollment: returns the secret and the URI to scan. Nothing changes
+/// at login until the first code is confirmed.
+pub async fn setup_mfa(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let mut conn = pool.acquire().await?;
+    let secret = mfa::start_enrollment(&mut conn, current_user.id).await?;
+
+    Ok(Json(ApiResponse::success(MfaSetupResponse {
+        provisioning_uri: totp::provisioning_uri(&secret, &current_user.email, TOTP_ISSUER),
+        secret,
+    })))
+}
+
+/// Enables MFA with a first code from the authenticator and returns the recovery codes.
+pub async fn confirm_mfa(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Json(request): Json<MfaCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let mut tx = pool.begin().await?;
+
+    let settings = mfa::mfa_settings(&mut tx, current_user.id)
+        .await?
+        .ok_or(AppError::BadRequest("Start the two-factor setup first".to_string()))?;
+
+    if settings.enabled {
+        return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+    }
+
+    if !mfa::check_totp(&mut tx, current_user.id, &request.code).await? {
+        return Err(AppError::BadRequest("Invalid authentication code".to_string()));
+    }
+
+    let recovery_codes = mfa::enable_mfa(&mut tx, current_user.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "recovery_codes": recovery_codes
+    }))))
+}
+
+/// Turns MFA off with a current code or a recovery code, unless the role requires it.
+pub async fn remove_mfa(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Json(request): Json<MfaCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let mut tx = pool.begin().await?;
+
+    if mfa::role_requires_mfa(&mut tx, current_user.role.as_str()).await? {
+        return Err(AppError::BadRequest("Two-factor authentication is required for your role".to_string()));
+    }
+
+    let enabled = mfa::mfa_settings(&mut tx, current_user.id)
+        .await?
+        .is_some_and(|settings| settings.enabled);
+
+    if !enabled {
+        return Err(AppError::BadRequest("Two-factor authentication is not enabled".to_string()));
+    }
+
+    if !mfa::check_second_factor(&mut tx, current_user.id, &request.code).await? {
+        return Err(AppError::BadRequest("Invalid authentication code".to_string()));
+    }
+
+    mfa::disable_mfa(&mut tx, current_user.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"enabled": false}))))
+}
+
+/// Replaces the recovery codes. Needs a code from the authenticator itself.
+pub async fn regenerate_recovery_codes(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Json(request): Json<MfaCodeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let mut tx = pool.begin().await?;
+
+    let enabled = mfa::mfa_settings(&mut tx, current_user.id)
+        .await?
+        .is_some_and(|settings| settings.enabled);
+
+    if !enabled {
+        return Err(AppError::BadRequest("Two-factor authentication is not enabled".to_string()));
+    }
+
+    if !mfa::check_totp(&mut tx, current_user.id, &request.code).await? {
+        return Err(AppError::BadRequest("Invalid authentication code".to_string()));
+    }
+
+    let recovery_codes = mfa::replace_recovery_codes(&mut tx, current_user.id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "recovery_codes": recovery_codes
+    }))))
+}
+
+pub async fn get_mfa_policies(
+    State(pool): State<PgPool>,
+) -> Result<impl IntoResponse, AppError> {
+    let policies = sqlx::query_as!(
+        MfaRolePolicy,
+        "SELECT role, require_mfa, updated_at FROM mfa_role_policies ORDER BY role"
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(policies)))
+}
+
+/// Requires (or stops requiring) MFA for every account with the role. Users
+/// without an authenticator are asked to enroll at their next login.
+pub async fn update_mfa_policy(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(role): Path<String>,
+    Json(request): Json<UpdateMfaPolicyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = Role::parse(&role)
+        .ok_or(AppError::BadRequest("Invalid role. Must be one of: admin, client, worker".to_string()))?;
+
+    let policy = sqlx::query_as!(
+        MfaRolePolicy,
+        r#"
+        INSERT INTO mfa_role_policies (role, require_mfa, updated_at, updated_by)
+        VALUES ($1, $2, NOW(), $3)
+        ON CONFLICT (role) DO UPDATE
+        SET require_mfa = EXCLUDED.require_mfa, updated_at = NOW(), updated_by = EXCLUDED.updated_by
+        RETURNING role, require_mfa, updated_at
+        "#,
+        role.as_str(),
+        request.require_mfa,
+        current_user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(policy)))
+}
@@ -1,13 +1,35 @@
 pub mod clients;
 pub mod workers;
 pub mod jobs;
+pub mod applications;
+pub mod pipeline;
+pub mod offers;
+pub mod placements;
+pub mod invoices;
 pub mod meetings;
 pub mod auth;
+pub mod users;
+pub mod mfa;
+pub mod passkeys;
+pub mod api_keys;
+pub mod sso;
+pub mod team;
 pub mod matching;
 
 pub use clients::*;
 pub use workers::*;
 pub use jobs::*;
+pub use applications::*;
+pub use pipeline::*;
+pub use offers::*;
+pub use placements::*;
+pub use invoices::*;
 pub use meetings::*;
 pub use auth::*;
+pub use users::*;
+pub use mfa::*;
+pub use passkeys::*;
+pub use api_keys::*;
+pub use sso::*;
+pub use team::*;
 pub use matching::*;
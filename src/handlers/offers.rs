@@ -0,0 +1,532 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::error::AppError;
+use crate::permissions::{authorize_application, authorize_offer, Role};
+use crate::auth::CurrentUser;
+use crate::utils::{ApiResponse, is_valid_salary, normalize_currency, MAX_SALARY};
+use super::applications::transition_application;
+use super::placements::insert_placement;
+
+fn validate_offer_terms(request: &OfferTermsRequest) -> Result<String, AppError> {
+    if !is_valid_salary(request.salary) {
+        return Err(AppError::BadRequest(format!("Salary must be between 1 and {}", MAX_SALARY)));
+    }
+
+    if request.expires_at <= Utc::now() {
+        return Err(AppError::BadRequest("Offer expiry must be in the future".to_string()));
+    }
+
+    normalize_currency(request.currency.as_deref())
+        .ok_or(AppError::BadRequest("Currency must be a 3-letter ISO code".to_string()))
+}
+
+fn trimmed(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Candidates see an offer's terms, not the agency's internal notes.
+fn as_seen_by(current_user: &CurrentUser, mut offer: Offer) -> Offer {
+    if current_user.role == Role::Worker {
+        offer.notes = None;
+    }
+    offer
+}
+
+async fn record_revision(
+    conn: &mut PgConnection,
+    offer: &Offer,
+    event: &str,
+    actor: Option<&CurrentUser>,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO offer_revisions (id, offer_id, version, event, salary, currency, start_date, expires_at,
+                                     status, notes, changed_by, changed_by_email, reason)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        "#,
+        Uuid::new_v4(),
+        offer.id,
+        offer.version,
+        event,
+        offer.salary,
+        offer.currency,
+        offer.start_date,
+        offer.expires_at,
+        offer.status.as_str(),
+        offer.notes,
+        actor.map(|actor| actor.id),
+        actor.map(|actor| actor.email.clone()),
+        reason
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+async fn lock_offer(conn: &mut PgConnection, id: Uuid) -> Result<Offer, AppError> {
+    let offer = sqlx::query_as!(
+        Offer,
+        r#"
+        SELECT id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+               notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        FROM offers
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        id
+    )
+    .fetch_optional(conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(offer)
+}
+
+async fn change_offer_status(
+    conn: &mut PgConnection,
+    offer: &Offer,
+    next: OfferStatus,
+    actor: Option<&CurrentUser>,
+    reason: Option<&str>,
+) -> Result<Offer, AppError> {
+    if !offer.status.can_transition_to(next) {
+        return Err(AppError::BadRequest(format!(
+            "Cannot move offer from {} to {}",
+            offer.status, next
+        )));
+    }
+
+    let updated = sqlx::query_as!(
+        Offer,
+        r#"
+        UPDATE offers
+        SET status = $1,
+            sent_at = CASE WHEN $1 = 'sent' THEN NOW() ELSE sent_at END,
+            responded_at = CASE WHEN $1 IN ('accepted', 'declined') THEN NOW() ELSE responded_at END,
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+                  notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        "#,
+        next.as_str(),
+        offer.id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_revision(conn, &updated, next.as_str(), actor, reason).await?;
+
+    Ok(updated)
+}
+
+/// Rescinds the application's draft and sent offers once it is rejected or withdrawn,
+/// so the candidate is not left with an offer that can never be accepted. Runs on the
+/// caller's transaction, after the application row has been locked.
+pub(crate) async fn rescind_open_offers(
+    conn: &mut PgConnection,
+    application_id: Uuid,
+    actor: &CurrentUser,
+    reason: &str,
+) -> Result<(), AppError> {
+    let open_offers = sqlx::query_as!(
+        Offer,
+        r#"
+        SELECT id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+               notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        FROM offers
+        WHERE application_id = $1 AND status IN ('draft', 'sent')
+        FOR UPDATE
+        "#,
+        application_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for offer in &open_offers {
+        change_offer_status(&mut *conn, offer, OfferStatus::Rescinded, Some(actor), Some(reason)).await?;
+    }
+
+    Ok(())
+}
+
+/// Deactivates the job once it has as many hires as its headcount. Jobs without a
+/// headcount stay open.
+async fn close_job_if_filled(conn: &mut PgConnection, job_id: Uuid) -> Result<bool, AppError> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE job_postings j
+        SET is_active = false, updated_at = NOW()
+        WHERE j.id = $1
+          AND j.is_active = true
+          AND j.headcount IS NOT NULL
+          AND (SELECT COUNT(*) FROM applications a WHERE a.job_id = j.id AND a.status = 'hired') >= j.headcount
+        "#,
+        job_id
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn create_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(application_id): Path<Uuid>,
+    Json(request): Json<OfferTermsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let currency = validate_offer_terms(&request)?;
+
+    let status = sqlx::query_scalar!(
+        r#"SELECT status as "status: ApplicationStatus" FROM applications WHERE id = $1"#,
+        application_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if !matches!(status, ApplicationStatus::Interview | ApplicationStatus::Offer) {
+        return Err(AppError::BadRequest(format!(
+            "Offers can only be made on applications at interview or offer stage, not {}",
+            status
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let offer = sqlx::query_as!(
+        Offer,
+        r#"
+        INSERT INTO offers (id, application_id, salary, currency, start_date, expires_at, status, notes, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+                  notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        "#,
+        Uuid::new_v4(),
+        application_id,
+        request.salary,
+        currency,
+        request.start_date,
+        request.expires_at,
+        OfferStatus::Draft.as_str(),
+        trimmed(&request.notes),
+        current_user.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        // Partial unique index: one draft or sent offer per application
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::Conflict("This application already has an open offer".to_string())
+        }
+        e => AppError::from(e),
+    })?;
+
+    record_revision(&mut tx, &offer, "created", Some(&current_user), trimmed(&request.reason)).await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(offer))))
+}
+
+pub async fn get_application_offers(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(application_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_application(&pool, &current_user, application_id).await?;
+
+    // Drafts stay with the agency until they are sent
+    let offers = sqlx::query_as!(
+        Offer,
+        r#"
+        SELECT id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+               notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        FROM offers
+        WHERE application_id = $1 AND (NOT $2 OR status <> 'draft')
+        ORDER BY created_at DESC
+        "#,
+        application_id,
+        current_user.role == Role::Worker
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let offers: Vec<Offer> = offers.into_iter().map(|offer| as_seen_by(&current_user, offer)).collect();
+
+    Ok(Json(ApiResponse::success(offers)))
+}
+
+pub async fn get_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_offer(&pool, &current_user, id).await?;
+
+    let offer = sqlx::query_as!(
+        Offer,
+        r#"
+        SELECT id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+               notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        FROM offers
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    match offer {
+        Some(offer) => Ok(Json(ApiResponse::success(as_seen_by(&current_user, offer)))),
+        None => Err(AppError::NotFound),
+    }
+}
+
+pub async fn get_offer_revisions(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    // The history holds earlier drafts and internal notes
+    if current_user.role == Role::Worker {
+        return Err(AppError::Forbidden);
+    }
+
+    authorize_offer(&pool, &current_user, id).await?;
+
+    let revisions = sqlx::query_as!(
+        OfferRevision,
+        r#"
+        SELECT id, offer_id, version, event, salary, currency, start_date, expires_at,
+               status as "status: OfferStatus", notes, changed_by, changed_by_email, reason, created_at
+        FROM offer_revisions
+        WHERE offer_id = $1
+        ORDER BY created_at ASC
+        "#,
+        id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    if revisions.is_empty() {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(ApiResponse::success(revisions)))
+}
+
+/// Changes the terms of a draft or sent offer. The version is bumped and a sent offer
+/// goes back to draft, so the candidate never accepts terms they were not sent.
+pub async fn revise_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<OfferTermsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let currency = validate_offer_terms(&request)?;
+
+    let mut tx = pool.begin().await?;
+    let offer = lock_offer(&mut tx, id).await?;
+
+    if !offer.status.is_open() {
+        return Err(AppError::BadRequest(format!("A {} offer can no longer be revised", offer.status)));
+    }
+
+    let offer = sqlx::query_as!(
+        Offer,
+        r#"
+        UPDATE offers
+        SET salary = $1, currency = $2, start_date = $3, expires_at = $4, notes = $5,
+            status = 'draft', version = version + 1, updated_at = NOW()
+        WHERE id = $6
+        RETURNING id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+                  notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        "#,
+        request.salary,
+        currency,
+        request.start_date,
+        request.expires_at,
+        trimmed(&request.notes),
+        offer.id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    record_revision(&mut tx, &offer, "revised", Some(&current_user), trimmed(&request.reason)).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(offer)))
+}
+
+pub async fn send_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    // The application is locked before the offer, in the order `transition_application` uses
+    let application_status = sqlx::query_scalar!(
+        r#"
+        SELECT a.status as "status: ApplicationStatus"
+        FROM applications a
+        JOIN offers o ON o.application_id = a.id
+        WHERE o.id = $1
+        FOR UPDATE OF a
+        "#,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let offer = lock_offer(&mut tx, id).await?;
+
+    if offer.expires_at <= Utc::now() {
+        return Err(AppError::BadRequest("Offer expiry is in the past; revise it before sending".to_string()));
+    }
+
+    // A closed application could never accept it
+    if !matches!(application_status, ApplicationStatus::Interview | ApplicationStatus::Offer) {
+        return Err(AppError::BadRequest(format!(
+            "Offers can only be sent on applications at interview or offer stage, not {}",
+            application_status
+        )));
+    }
+
+    // Sending the first offer moves the candidate into the offer stage
+    if application_status == ApplicationStatus::Interview {
+        transition_application(&mut tx, offer.application_id, ApplicationStatus::Offer, &current_user, Some("Offer sent")).await?;
+    }
+
+    let offer = change_offer_status(&mut tx, &offer, OfferStatus::Sent, Some(&current_user), None).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(offer)))
+}
+
+pub async fn accept_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<OfferActionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_offer(&pool, &current_user, id).await?;
+
+    let mut tx = pool.begin().await?;
+    let offer = lock_offer(&mut tx, id).await?;
+
+    // The sweep may not have run yet; never accept an offer past its deadline
+    if offer.is_overdue(Utc::now()) {
+        change_offer_status(&mut tx, &offer, OfferStatus::Expired, None, Some("Offer expired")).await?;
+        tx.commit().await?;
+        return Err(AppError::BadRequest("Offer has expired".to_string()));
+    }
+
+    let offer = change_offer_status(&mut tx, &offer, OfferStatus::Accepted, Some(&current_user), trimmed(&request.reason)).await?;
+
+    let application = transition_application(
+        &mut tx,
+        offer.application_id,
+        ApplicationStatus::Hired,
+        &current_user,
+        Some("Offer accepted"),
+    )
+    .await?;
+
+    // An accepted offer is a placement the agency can bill for
+    let placement = insert_placement(
+        &mut tx,
+        &application,
+        offer.start_date,
+        offer.salary,
+        &offer.currency,
+        &current_user,
+    )
+    .await?;
+
+    let job_closed = close_job_if_filled(&mut tx, application.job_id).await?;
+
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "offer": as_seen_by(&current_user, offer),
+        "application": application,
+        "placement": placement,
+        "job_closed": job_closed
+    }))))
+}
+
+pub async fn decline_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<OfferActionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_offer(&pool, &current_user, id).await?;
+
+    let mut tx = pool.begin().await?;
+    let offer = lock_offer(&mut tx, id).await?;
+    let offer = change_offer_status(&mut tx, &offer, OfferStatus::Declined, Some(&current_user), trimmed(&request.reason)).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(as_seen_by(&current_user, offer))))
+}
+
+pub async fn rescind_offer(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<OfferActionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let reason = trimmed(&request.reason)
+        .ok_or(AppError::BadRequest("A reason is required when rescinding an offer".to_string()))?;
+
+    let mut tx = pool.begin().await?;
+    let offer = lock_offer(&mut tx, id).await?;
+    let offer = change_offer_status(&mut tx, &offer, OfferStatus::Rescinded, Some(&current_user), Some(reason)).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(offer)))
+}
+
+/// Marks every sent offer past its `expires_at` as expired (see `Offer::is_overdue`).
+/// Called periodically from `main`; returns the number of offers that expired.
+pub async fn expire_overdue_offers(pool: &PgPool) -> Result<usize, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let expired = sqlx::query_as!(
+        Offer,
+        r#"
+        UPDATE offers
+        SET status = 'expired', updated_at = NOW()
+        WHERE status = 'sent' AND expires_at <= NOW()
+        RETURNING id, application_id, salary, currency, start_date, expires_at, status as "status: OfferStatus",
+                  notes, version, created_by, sent_at, responded_at, created_at, updated_at
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for offer in &expired {
+        record_revision(&mut tx, offer, OfferStatus::Expired.as_str(), None, Some("Offer expired")).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(expired.len())
+}
@@ -0,0 +1,114 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::{AuthService, CurrentUser, SessionDevice};
+use crate::error::AppError;
+use crate::models::*;
+use crate::utils::ApiResponse;
+
+pub async fn get_passkeys(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    let passkeys = sqlx::query_as!(
+        Passkey,
+        r#"
+        SELECT id, name, created_at, last_used_at
+        FROM webauthn_credentials
+        WHERE user_id = $1
+        ORDER BY created_at
+        "#,
+        current_user.id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(passkeys)))
+}
+
+/// First step of adding a passkey: the options to pass to `navigator.credentials.create()`.
+pub async fn start_passkey_registration(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let options = auth_service
+        .start_passkey_registration(&pool, current_user.id, &current_user.email)
+        .await?;
+
+    Ok(Json(ApiResponse::success(options)))
+}
+
+pub async fn finish_passkey_registration(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Json(request): Json<FinishPasskeyRegistrationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let name = request.name.as_deref().map(str::trim).unwrap_or_default();
+    if name.len() > 100 {
+        return Err(AppError::BadRequest("Passkey name must be at most 100 characters".to_string()));
+    }
+    let name = if name.is_empty() { "Passkey" } else { name };
+
+    let passkey = auth_service
+        .finish_passkey_registration(&pool, current_user.id, name, &request.credential)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(passkey))))
+}
+
+pub async fn delete_passkey(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(passkey_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.ensure_not_impersonating()?;
+
+    let result = sqlx::query!(
+        "DELETE FROM webauthn_credentials WHERE id = $1 AND user_id = $2",
+        passkey_id,
+        current_user.id
+    )
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(ApiResponse::success(json!({"deleted": true}))))
+}
+
+/// First step of a passkey login: the options to pass to `navigator.credentials.get()`.
+pub async fn start_passkey_login(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+) -> Result<impl IntoResponse, AppError> {
+    let options = auth_service.start_passkey_login(&pool).await?;
+
+    Ok(Json(ApiResponse::success(options)))
+}
+
+/// Exchanges the authenticator's signed assertion for a session.
+pub async fn finish_passkey_login(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    device: SessionDevice,
+    Json(request): Json<FinishPasskeyLoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = auth_service.finish_passkey_login(&pool, &request.credential, &device).await?;
+
+    Ok(Json(ApiResponse::success(session)))
+}
@@ -0,0 +1,644 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::error::AppError;
+use crate::permissions::{authorize_application, authorize_job};
+use crate::auth::CurrentUser;
+use crate::utils::{ApiResponse, calculate_skill_match_score};
+use super::applications::transition_application;
+
+// Number of matching skills shown on a board card
+const TOP_SKILLS_ON_CARD: usize = 3;
+
+/// Stages every new job starts with: (name, is_terminal, is_success).
+/// Keep in sync with the backfill in the initial migration and with `seeds/demo.sql`.
+pub const DEFAULT_PIPELINE_STAGES: [(&str, bool, bool); 6] = [
+    ("Applied", false, false),
+    ("Screening", false, false),
+    ("Interview", false, false),
+    ("Offer", false, false),
+    ("Hired", true, true),
+    ("Rejected", true, false),
+];
+
+pub(crate) async fn create_default_stages(conn: &mut PgConnection, job_id: Uuid) -> Result<(), AppError> {
+    for (position, (name, is_terminal, is_success)) in DEFAULT_PIPELINE_STAGES.iter().enumerate() {
+        sqlx::query!(
+            r#"
+            INSERT INTO job_pipeline_stages (id, job_id, name, position, is_terminal, is_success)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            Uuid::new_v4(),
+            job_id,
+            *name,
+            position as i32,
+            *is_terminal,
+            *is_success
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Status an application holds while it sits in an open stage, from the stage's place
+/// among the job's open stages: the first is pending and the last offer, the one before
+/// the last is interview once there are four or more, and any others are reviewing.
+pub fn status_for_open_stage(index: usize, open_stages: usize) -> ApplicationStatus {
+    if index == 0 {
+        ApplicationStatus::Pending
+    } else if index + 1 == open_stages {
+        ApplicationStatus::Offer
+    } else if index + 2 == open_stages && open_stages >= 4 {
+        ApplicationStatus::Interview
+    } else {
+        ApplicationStatus::Reviewing
+    }
+}
+
+/// Status an application in `stage_id` should hold. Terminal stages hire or reject.
+pub fn stage_status(stages: &[PipelineStage], stage_id: Uuid) -> Option<ApplicationStatus> {
+    let stage = stages.iter().find(|stage| stage.id == stage_id)?;
+
+    if stage.is_terminal {
+        return Some(if stage.is_success { ApplicationStatus::Hired } else { ApplicationStatus::Rejected });
+    }
+
+    let mut open: Vec<&PipelineStage> = stages.iter().filter(|stage| !stage.is_terminal).collect();
+    open.sort_by_key(|stage| stage.position);
+
+    let index = open.iter().position(|stage| stage.id == stage_id)?;
+    Some(status_for_open_stage(index, open.len()))
+}
+
+/// Stages in `occupied` whose status (see `stage_status`) would change if the pipeline
+/// became `requested`. The applications in them would no longer hold the status of their stage.
+pub fn remapped_stages(
+    current: &[PipelineStage],
+    requested: &[PipelineStageInput],
+    occupied: &HashSet<Uuid>,
+) -> Vec<Uuid> {
+    let proposed: Vec<PipelineStage> = requested
+        .iter()
+        .enumerate()
+        .map(|(position, input)| {
+            let existing = input.id.and_then(|id| current.iter().find(|stage| stage.id == id));
+            PipelineStage {
+                id: existing.map_or_else(Uuid::new_v4, |stage| stage.id),
+                job_id: existing.map_or_else(Uuid::nil, |stage| stage.job_id),
+                name: input.name.trim().to_string(),
+                position: position as i32,
+                is_terminal: input.is_terminal.unwrap_or(false),
+                is_success: input.is_success.unwrap_or(false),
+                created_at: existing.map_or_else(Utc::now, |stage| stage.created_at),
+                updated_at: None,
+            }
+        })
+        .collect();
+
+    current
+        .iter()
+        .filter(|stage| occupied.contains(&stage.id))
+        .filter(|stage| stage_status(current, stage.id) != stage_status(&proposed, stage.id))
+        .map(|stage| stage.id)
+        .collect()
+}
+
+pub(crate) async fn lock_job_stages(conn: &mut PgConnection, job_id: Uuid) -> Result<Vec<PipelineStage>, AppError> {
+    let stages = sqlx::query_as!(
+        PipelineStage,
+        r#"
+        SELECT id, job_id, name, position, is_terminal, is_success, created_at, updated_at
+        FROM job_pipeline_stages
+        WHERE job_id = $1
+        ORDER BY position ASC
+        FOR SHARE
+        "#,
+        job_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(stages)
+}
+
+/// Puts an application that moved to an open `status` in the first stage matching it,
+/// unless its current stage already does. Pipelines without such a stage keep the card
+/// where it is.
+pub(crate) async fn sync_stage_with_status(
+    conn: &mut PgConnection,
+    application: Application,
+) -> Result<Application, AppError> {
+    let stages = lock_job_stages(&mut *conn, application.job_id).await?;
+    let status_of = |stage_id: Uuid| stage_status(&stages, stage_id);
+
+    if application.stage_id.and_then(status_of) == Some(application.status) {
+        return Ok(application);
+    }
+
+    let Some(stage) = stages.iter().find(|stage| status_of(stage.id) == Some(application.status)) else {
+        return Ok(application);
+    };
+
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        UPDATE applications
+        SET stage_id = $1, stage_entered_at = NOW(),
+            stage_position = (SELECT COALESCE(MAX(stage_position) + 1, 0) FROM applications WHERE stage_id = $1)
+        WHERE id = $2
+        RETURNING id, job_id, worker_id, status as "status: ApplicationStatus", stage_id, stage_entered_at,
+                  cover_letter, applied_at, updated_at
+        "#,
+        stage.id,
+        application.id
+    )
+    .fetch_one(conn)
+    .await?;
+
+    Ok(application)
+}
+
+async fn fetch_job_stages(pool: &PgPool, job_id: Uuid) -> Result<Vec<PipelineStage>, AppError> {
+    let stages = sqlx::query_as!(
+        PipelineStage,
+        r#"
+        SELECT id, job_id, name, position, is_terminal, is_success, created_at, updated_at
+        FROM job_pipeline_stages
+        WHERE job_id = $1
+        ORDER BY position ASC
+        "#,
+        job_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stages)
+}
+
+pub fn validate_pipeline(stages: &[PipelineStageInput]) -> Result<(), AppError> {
+    if stages.is_empty() {
+        return Err(AppError::BadRequest("A pipeline needs at least one stage".to_string()));
+    }
+
+    let mut names = HashSet::new();
+    for stage in stages {
+        let name = stage.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("Stage name is required".to_string()));
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(AppError::BadRequest(format!("Duplicate stage name: {}", name)));
+        }
+        if stage.is_success.unwrap_or(false) && !stage.is_terminal.unwrap_or(false) {
+            return Err(AppError::BadRequest(format!("Success stage '{}' must also be terminal", name)));
+        }
+    }
+
+    // New applications enter on the first stage, so it must still be open
+    if stages[0].is_terminal.unwrap_or(false) {
+        return Err(AppError::BadRequest("The first stage cannot be terminal".to_string()));
+    }
+
+    Ok(())
+}
+
+pub async fn get_job_stages(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    let existing = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM job_postings WHERE id = $1",
+        job_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if existing.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    let stages = fetch_job_stages(&pool, job_id).await?;
+    Ok(Json(ApiResponse::success(stages)))
+}
+
+/// Replaces a job's pipeline with the given ordered list. Stages sent with an `id` are
+/// kept (and renamed/reordered), stages without one are created, and stages left out
+/// are removed - which is refused while applications still sit in them. Stages holding
+/// applications can be renamed, but not moved or changed in a way that changes their status.
+pub async fn update_job_stages(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<UpdatePipelineRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    validate_pipeline(&request.stages)?;
+
+    let mut tx = pool.begin().await?;
+
+    // One pipeline edit per job at a time
+    sqlx::query_scalar!("SELECT id FROM job_postings WHERE id = $1 FOR UPDATE", job_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let current = lock_job_stages(&mut tx, job_id).await?;
+    let current_ids: HashSet<Uuid> = current.iter().map(|stage| stage.id).collect();
+
+    let kept_ids: HashSet<Uuid> = request.stages.iter().filter_map(|stage| stage.id).collect();
+    if let Some(unknown) = kept_ids.difference(&current_ids).next() {
+        return Err(AppError::BadRequest(format!("Stage {} does not belong to this job", unknown)));
+    }
+
+    let occupied: HashSet<Uuid> = sqlx::query_scalar!(
+        "SELECT DISTINCT stage_id as \"stage_id!\" FROM applications WHERE job_id = $1 AND stage_id IS NOT NULL",
+        job_id
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    if current_ids.difference(&kept_ids).any(|removed| occupied.contains(removed)) {
+        return Err(AppError::BadRequest(
+            "Move applications out of a stage before removing it".to_string()
+        ));
+    }
+
+    // Statuses only change through the state machine, so a stage holding applications
+    // must keep standing for the same status
+    if let Some(remapped) = remapped_stages(&current, &request.stages, &occupied).first() {
+        let name = current.iter().find(|stage| stage.id == *remapped).map_or("", |stage| stage.name.as_str());
+        return Err(AppError::BadRequest(format!(
+            "Stage '{}' holds applications; this change would give it another status. Move them out first",
+            name
+        )));
+    }
+
+    for removed in current_ids.difference(&kept_ids) {
+        sqlx::query!("DELETE FROM job_pipeline_stages WHERE id = $1", removed)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for (position, stage) in request.stages.iter().enumerate() {
+        let is_terminal = stage.is_terminal.unwrap_or(false);
+        let is_success = stage.is_success.unwrap_or(false);
+
+        match stage.id {
+            Some(id) => {
+                sqlx::query!(
+                    r#"
+                    UPDATE job_pipeline_stages
+                    SET name = $1, position = $2, is_terminal = $3, is_success = $4, updated_at = NOW()
+                    WHERE id = $5
+                    "#,
+                    stage.name.trim(),
+                    position as i32,
+                    is_terminal,
+                    is_success,
+                    id
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO job_pipeline_stages (id, job_id, name, position, is_terminal, is_success)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    "#,
+                    Uuid::new_v4(),
+                    job_id,
+                    stage.name.trim(),
+                    position as i32,
+                    is_terminal,
+                    is_success
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    let stages = fetch_job_stages(&pool, job_id).await?;
+    Ok(Json(ApiResponse::success(stages)))
+}
+
+/// Moves an application to another stage of its job's pipeline, walking the status
+/// state machine forward to the status the stage stands for (see `stage_status`), so
+/// every step lands in the history. Success stages hire and other terminal stages
+/// reject. Moving back to a stage behind the current status is refused, as statuses
+/// never go back.
+pub(crate) async fn move_to_stage(
+    conn: &mut PgConnection,
+    application_id: Uuid,
+    stage_id: Uuid,
+    actor: &CurrentUser,
+    reason: Option<&str>,
+) -> Result<Application, AppError> {
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        SELECT id, job_id, worker_id, status as "status: ApplicationStatus", stage_id, stage_entered_at,
+               cover_letter, applied_at, updated_at
+        FROM applications
+        WHERE id = $1
+        FOR UPDATE
+        "#,
+        application_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if application.status.is_terminal() {
+        return Err(AppError::BadRequest(format!("Application is already {}", application.status)));
+    }
+
+    let stages = lock_job_stages(&mut *conn, application.job_id).await?;
+    let stage = stages
+        .iter()
+        .find(|stage| stage.id == stage_id)
+        .ok_or(AppError::BadRequest("Stage does not belong to this job's pipeline".to_string()))?;
+
+    if application.stage_id == Some(stage.id) {
+        return Ok(application);
+    }
+
+    let target = stage_status(&stages, stage.id).unwrap_or(application.status);
+    let steps = application.status.path_to(target).ok_or_else(|| AppError::BadRequest(format!(
+        "Application is already {}; it cannot move back to stage '{}'",
+        application.status, stage.name
+    )))?;
+
+    if target == ApplicationStatus::Rejected && reason.is_none() {
+        return Err(AppError::BadRequest("A reason is required when rejecting an application".to_string()));
+    }
+
+    for step in steps {
+        transition_application(&mut *conn, application_id, step, actor, reason).await?;
+    }
+
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        UPDATE applications
+        SET stage_id = $1,
+            stage_entered_at = CASE WHEN stage_id IS DISTINCT FROM $1 THEN NOW() ELSE stage_entered_at END,
+            stage_position = CASE WHEN stage_id IS DISTINCT FROM $1
+                THEN (SELECT COALESCE(MAX(stage_position) + 1, 0) FROM applications WHERE stage_id = $1)
+                ELSE stage_position END,
+            updated_at = NOW()
+        WHERE id = $2
+        RETURNING id, job_id, worker_id, status as "status: ApplicationStatus", stage_id, stage_entered_at,
+                  cover_letter, applied_at, updated_at
+        "#,
+        stage.id,
+        application_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(application)
+}
+
+pub async fn move_application_stage(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<MoveApplicationStageRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_application(&pool, &current_user, id).await?;
+
+    let reason = request.reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty());
+
+    let mut tx = pool.begin().await?;
+    let application = move_to_stage(&mut tx, id, request.stage_id, &current_user, reason).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(application)))
+}
+
+async fn build_board(pool: &PgPool, job_id: Uuid) -> Result<PipelineBoard, AppError> {
+    let requirements = sqlx::query_scalar!(
+        "SELECT requirements FROM job_postings WHERE id = $1",
+        job_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound)?
+    .unwrap_or_default();
+
+    let stages = fetch_job_stages(pool, job_id).await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT a.id, a.status as "status: ApplicationStatus", a.stage_id, a.stage_position,
+               a.stage_entered_at, a.applied_at,
+               w.id as worker_id, w.name as worker_name, w.skills as worker_skills
+        FROM applications a
+        JOIN workers w ON a.worker_id = w.id
+        WHERE a.job_id = $1
+        ORDER BY a.stage_position ASC, a.applied_at ASC
+        "#,
+        job_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let now = chrono::Utc::now();
+
+    let cards = rows
+        .into_iter()
+        .map(|row| {
+            let worker_skills = row.worker_skills.unwrap_or_default();
+            let top_matching_skills: Vec<String> = worker_skills
+                .iter()
+                .filter(|skill| requirements.contains(skill))
+                .take(TOP_SKILLS_ON_CARD)
+                .cloned()
+                .collect();
+
+            let entered_at = row.stage_entered_at.unwrap_or(row.applied_at);
+
+            let card = BoardCard {
+                application_id: row.id,
+                status: row.status,
+                position: row.stage_position,
+                days_in_stage: (now - entered_at).num_days(),
+                applied_at: row.applied_at,
+                worker: BoardWorkerSummary {
+                    id: row.worker_id,
+                    name: row.worker_name,
+                    top_matching_skills,
+                    match_score: calculate_skill_match_score(&requirements, &worker_skills),
+                },
+            };
+
+            (row.stage_id, card)
+        })
+        .collect();
+
+    Ok(group_board(job_id, stages, cards))
+}
+
+/// Lays cards out in their stage's column, keeping the order they come in.
+/// Applications without a (known) stage are shown in the entry column.
+pub fn group_board(job_id: Uuid, stages: Vec<PipelineStage>, cards: Vec<(Option<Uuid>, BoardCard)>) -> PipelineBoard {
+    let total = cards.len();
+
+    let mut columns: Vec<BoardColumn> = stages
+        .into_iter()
+        .map(|stage| BoardColumn { stage, count: 0, cards: Vec::new() })
+        .collect();
+
+    for (stage_id, card) in cards {
+        let column = columns
+            .iter()
+            .position(|column| Some(column.stage.id) == stage_id)
+            .unwrap_or(0);
+
+        if let Some(column) = columns.get_mut(column) {
+            column.cards.push(card);
+        }
+    }
+
+    for column in &mut columns {
+        column.count = column.cards.len();
+    }
+
+    PipelineBoard { job_id, total, columns }
+}
+
+pub async fn get_pipeline_board(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    let board = build_board(&pool, job_id).await?;
+    Ok(Json(ApiResponse::success(board)))
+}
+
+/// A column's cards after putting `card` at `position`, clamped to the end of the column.
+pub fn place_card(column: &[Uuid], card: Uuid, position: usize) -> Vec<Uuid> {
+    let mut column: Vec<Uuid> = column.iter().copied().filter(|id| *id != card).collect();
+    column.insert(position.min(column.len()), card);
+    column
+}
+
+/// Card ids of a stage in board order, locked for renumbering.
+async fn column_order(conn: &mut PgConnection, stage_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+    let column = sqlx::query_scalar!(
+        r#"
+        SELECT id FROM applications
+        WHERE stage_id = $1
+        ORDER BY stage_position ASC, applied_at ASC
+        FOR UPDATE
+        "#,
+        stage_id
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(column)
+}
+
+/// Numbers a column's cards 0, 1, 2... in the given order.
+async fn write_column_order(conn: &mut PgConnection, column: &[Uuid]) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        UPDATE applications a
+        SET stage_position = (o.ordinality - 1)::int
+        FROM UNNEST($1::uuid[]) WITH ORDINALITY AS o(id, ordinality)
+        WHERE a.id = o.id
+        "#,
+        column
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Applies a batch of card moves and reorders in one transaction, then returns the
+/// refreshed board. A move into a different stage goes through the same rules as
+/// `move_application_stage`; a move within the same stage only changes the position.
+/// Both the target and the source column are renumbered from 0, so positions stay unique.
+pub async fn update_pipeline_board(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(job_id): Path<Uuid>,
+    Json(request): Json<UpdateBoardRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    authorize_job(&pool, &current_user, job_id).await?;
+
+    if request.moves.is_empty() {
+        return Err(AppError::BadRequest("At least one move is required".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for card_move in &request.moves {
+        if card_move.position < 0 {
+            return Err(AppError::BadRequest("Card position cannot be negative".to_string()));
+        }
+
+        let current_stage = sqlx::query_scalar!(
+            "SELECT stage_id FROM applications WHERE id = $1 AND job_id = $2",
+            card_move.application_id,
+            job_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::BadRequest(format!(
+            "Application {} does not belong to this job",
+            card_move.application_id
+        )))?;
+
+        if current_stage != Some(card_move.stage_id) {
+            let reason = card_move.reason
+                .as_deref()
+                .map(str::trim)
+                .filter(|reason| !reason.is_empty());
+
+            move_to_stage(&mut tx, card_move.application_id, card_move.stage_id, &current_user, reason).await?;
+        }
+
+        let column = column_order(&mut tx, card_move.stage_id).await?;
+        let column = place_card(&column, card_move.application_id, card_move.position as usize);
+        write_column_order(&mut tx, &column).await?;
+
+        // Close the gap the card left behind
+        if let Some(source) = current_stage.filter(|source| *source != card_move.stage_id) {
+            let column = column_order(&mut tx, source).await?;
+            write_column_order(&mut tx, &column).await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    let board = build_board(&pool, job_id).await?;
+    Ok(Json(ApiResponse::success(board)))
+}
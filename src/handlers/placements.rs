@@ -0,0 +1,264 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::error::AppError;
+use crate::auth::CurrentUser;
+use crate::utils::{ApiResponse, PaginationParams, PaginatedResponse, is_valid_salary, normalize_currency, MAX_SALARY};
+
+#[derive(Debug, Deserialize)]
+pub struct PlacementFilterQuery {
+    pub client_id: Option<Uuid>,
+    pub worker_id: Option<Uuid>,
+}
+
+/// Records a placement for a hired application. The client is taken from the job.
+pub(crate) async fn insert_placement(
+    conn: &mut PgConnection,
+    application: &Application,
+    start_date: NaiveDate,
+    agreed_salary: i64,
+    currency: &str,
+    actor: &CurrentUser,
+) -> Result<Placement, AppError> {
+    let placement = sqlx::query_as!(
+        Placement,
+        r#"
+        INSERT INTO placements (id, application_id, worker_id, job_id, client_id, start_date, agreed_salary, currency, created_by)
+        SELECT $1, $2, $3, j.id, j.client_id, $5, $6, $7, $8
+        FROM job_postings j
+        WHERE j.id = $4
+        RETURNING id, application_id, worker_id, job_id, client_id, start_date, agreed_salary, currency,
+                  created_by, created_at, updated_at
+        "#,
+        Uuid::new_v4(),
+        application.id,
+        application.worker_id,
+        application.job_id,
+        start_date,
+        agreed_salary,
+        currency,
+        actor.id
+    )
+    .fetch_one(conn)
+    .await
+    .map_err(|e| match e {
+        // placements.application_id is UNIQUE
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            AppError::Conflict("A placement already exists for this application".to_string())
+        }
+        e => AppError::from(e),
+    })?;
+
+    Ok(placement)
+}
+
+pub async fn get_placements(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Query(pagination): Query<PaginationParams>,
+    Query(filters): Query<PlacementFilterQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let client_id = current_user.client_filter(filters.client_id)?;
+    let pagination = PaginationParams::new(pagination.page, pagination.limit);
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM placements
+        WHERE ($1::uuid IS NULL OR client_id = $1) AND ($2::uuid IS NULL OR worker_id = $2)
+        "#,
+        client_id,
+        filters.worker_id
+    )
+    .fetch_one(&pool)
+    .await?
+    .unwrap_or(0);
+
+    let placements = sqlx::query_as!(
+        Placement,
+        r#"
+        SELECT id, application_id, worker_id, job_id, client_id, start_date, agreed_salary, currency,
+               created_by, created_at, updated_at
+        FROM placements
+        WHERE ($1::uuid IS NULL OR client_id = $1) AND ($2::uuid IS NULL OR worker_id = $2)
+        ORDER BY start_date DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        client_id,
+        filters.worker_id,
+        pagination.limit(),
+        pagination.offset()
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let response = PaginatedResponse::new(placements, pagination.page.unwrap_or(1), pagination.limit.unwrap_or(20), total);
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+pub async fn get_placement(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let placement = sqlx::query_as!(
+        Placement,
+        r#"
+        SELECT id, application_id, worker_id, job_id, client_id, start_date, agreed_salary, currency,
+               created_by, created_at, updated_at
+        FROM placements
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    current_user.authorize_client(placement.client_id)?;
+
+    Ok(Json(ApiResponse::success(placement)))
+}
+
+/// Records a placement for a candidate hired without going through an offer.
+/// Accepted offers create their placement automatically.
+pub async fn create_placement(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Json(request): Json<CreatePlacementRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if !is_valid_salary(request.agreed_salary) {
+        return Err(AppError::BadRequest(format!("Agreed salary must be between 1 and {}", MAX_SALARY)));
+    }
+
+    let currency = normalize_currency(request.currency.as_deref())
+        .ok_or(AppError::BadRequest("Currency must be a 3-letter ISO code".to_string()))?;
+
+    let application = sqlx::query_as!(
+        Application,
+        r#"
+        SELECT id, job_id, worker_id, status as "status: ApplicationStatus", stage_id, stage_entered_at,
+               cover_letter, applied_at, updated_at
+        FROM applications
+        WHERE id = $1
+        "#,
+        request.application_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::BadRequest("Application not found".to_string()))?;
+
+    if application.status != ApplicationStatus::Hired {
+        return Err(AppError::BadRequest("Only hired applications can be placed".to_string()));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let placement = insert_placement(
+        &mut conn,
+        &application,
+        request.start_date,
+        request.agreed_salary,
+        &currency,
+        &current_user,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(placement))))
+}
+
+pub async fn get_fee_agreement(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let agreement = sqlx::query_as!(
+        FeeAgreement,
+        r#"
+        SELECT id, client_id, fee_type as "fee_type: FeeType", percentage_bps, flat_fee, currency,
+               payment_terms_days, created_at, updated_at
+        FROM fee_agreements
+        WHERE client_id = $1
+        "#,
+        client_id
+    )
+    .fetch_optional(&pool)
+    .await?;
+
+    match agreement {
+        Some(agreement) => Ok(Json(ApiResponse::success(agreement))),
+        None => Err(AppError::NotFound),
+    }
+}
+
+/// Creates or replaces the client's fee agreement. Invoices already issued keep the
+/// amounts they were generated with.
+pub async fn upsert_fee_agreement(
+    State(pool): State<PgPool>,
+    Path(client_id): Path<Uuid>,
+    Json(request): Json<FeeAgreementRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let (percentage_bps, flat_fee) = match request.fee_type {
+        FeeType::Percentage => match request.percentage_bps {
+            Some(bps) if bps > 0 && bps <= 10_000 => (Some(bps), None),
+            _ => return Err(AppError::BadRequest("percentage_bps must be between 1 and 10000".to_string())),
+        },
+        FeeType::Flat => match request.flat_fee {
+            Some(fee) if fee > 0 => (None, Some(fee)),
+            _ => return Err(AppError::BadRequest("flat_fee must be positive".to_string())),
+        },
+    };
+
+    let payment_terms_days = request.payment_terms_days.unwrap_or(30);
+    if !(0..=365).contains(&payment_terms_days) {
+        return Err(AppError::BadRequest("Payment terms must be between 0 and 365 days".to_string()));
+    }
+
+    let currency = normalize_currency(request.currency.as_deref())
+        .ok_or(AppError::BadRequest("Currency must be a 3-letter ISO code".to_string()))?;
+
+    let client_exists = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM clients WHERE id = $1",
+        client_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if client_exists.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    let agreement = sqlx::query_as!(
+        FeeAgreement,
+        r#"
+        INSERT INTO fee_agreements (id, client_id, fee_type, percentage_bps, flat_fee, currency, payment_terms_days)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (client_id) DO UPDATE
+        SET fee_type = EXCLUDED.fee_type, percentage_bps = EXCLUDED.percentage_bps, flat_fee = EXCLUDED.flat_fee,
+            currency = EXCLUDED.currency, payment_terms_days = EXCLUDED.payment_terms_days, updated_at = NOW()
+        RETURNING id, client_id, fee_type as "fee_type: FeeType", percentage_bps, flat_fee, currency,
+                  payment_terms_days, created_at, updated_at
+        "#,
+        Uuid::new_v4(),
+        client_id,
+        request.fee_type.as_str(),
+        percentage_bps,
+        flat_fee,
+        currency,
+        payment_terms_days
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(agreement)))
+}
@@ -0,0 +1,322 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Redirect},
+    Json,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::{hash_token, AuthService, CurrentUser, SessionDevice};
+use crate::error::AppError;
+use crate::models::*;
+use crate::oidc;
+use crate::utils::{app_base_url, ApiResponse};
+
+// Time allowed between leaving for the identity provider and coming back
+const SSO_LOGIN_TTL_MINUTES: i64 = 10;
+
+fn sso_redirect_uri() -> String {
+    format!("{}/api/auth/sso/callback", app_base_url())
+}
+
+fn email_domain(email: &str) -> Option<&str> {
+    email.rsplit_once('@').map(|(_, domain)| domain)
+}
+
+pub async fn get_sso_config(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let config = sqlx::query_as!(
+        ClientSsoConfig,
+        "SELECT * FROM client_sso_configs WHERE client_id = $1",
+        client_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(config)))
+}
+
+/// Sets the client's identity provider. The issuer's discovery document must
+/// be reachable, so a typo is caught here rather than at the first login.
+pub async fn upsert_sso_config(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+    Json(request): Json<UpsertSsoConfigRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let issuer = request.issuer.trim().trim_end_matches('/').to_string();
+    if !issuer.starts_with("https://") && !issuer.starts_with("http://") {
+        return Err(AppError::BadRequest("Issuer must be an http(s) URL".to_string()));
+    }
+
+    if request.oidc_client_id.trim().is_empty() || request.oidc_client_secret.is_empty() {
+        return Err(AppError::BadRequest("OIDC client ID and secret are required".to_string()));
+    }
+
+    let allowed_email_domains: Vec<String> = request
+        .allowed_email_domains
+        .unwrap_or_default()
+        .iter()
+        .map(|domain| domain.trim().trim_start_matches('@').to_lowercase())
+        .filter(|domain| !domain.is_empty())
+        .collect();
+
+    let client_exists = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM clients WHERE id = $1",
+        client_id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if client_exists.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    if oidc::discover(&issuer).await.is_err() {
+        return Err(AppError::BadRequest(format!("No OpenID Connect provider found at {}", issuer)));
+    }
+
+    let config = sqlx::query_as!(
+        ClientSsoConfig,
+        r#"
+        INSERT INTO client_sso_configs (client_id, issuer, oidc_client_id, oidc_client_secret, allowed_email_domains, enabled)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (client_id) DO UPDATE
+        SET issuer = EXCLUDED.issuer,
+            oidc_client_id = EXCLUDED.oidc_client_id,
+            oidc_client_secret = EXCLUDED.oidc_client_secret,
+            allowed_email_domains = EXCLUDED.allowed_email_domains,
+            enabled = EXCLUDED.enabled,
+            updated_at = NOW()
+        RETURNING *
+        "#,
+        client_id,
+        issuer,
+        request.oidc_client_id.trim(),
+        request.oidc_client_secret,
+        &allowed_email_domains,
+        request.enabled.unwrap_or(true)
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(config)))
+}
+
+pub async fn delete_sso_config(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let result = sqlx::query!(
+        "DELETE FROM client_sso_configs WHERE client_id = $1",
+        client_id
+    )
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"deleted": true}))))
+}
+
+/// Sends the browser to the client's identity provider (authorization code
+/// flow with PKCE). The state, nonce and verifier wait in the database for
+/// the callback.
+pub async fn start_sso_login(
+    State(pool): State<PgPool>,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = sqlx::query_as!(
+        ClientSsoConfig,
+        "SELECT * FROM client_sso_configs WHERE client_id = $1 AND enabled = true",
+        client_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let metadata = oidc::discover(&config.issuer).await?;
+
+    let state = oidc::random_token();
+    let nonce = oidc::random_token();
+    let code_verifier = oidc::random_token();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO sso_login_states (state_hash, client_id, code_verifier, nonce, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+        hash_token(&state),
+        client_id,
+        code_verifier,
+        nonce,
+        Utc::now() + Duration::minutes(SSO_LOGIN_TTL_MINUTES)
+    )
+    .execute(&pool)
+    .await?;
+
+    let url = oidc::authorization_url(
+        &metadata,
+        &config.oidc_client_id,
+        &sso_redirect_uri(),
+        &state,
+        &nonce,
+        &code_verifier,
+    )?;
+
+    Ok(Redirect::to(&url))
+}
+
+/// Where the identity provider sends the browser back. Exchanges the code,
+/// verifies the ID token and answers like `login`.
+pub async fn sso_callback(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    device: SessionDevice,
+    Query(query): Query<SsoCallbackQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(error) = query.error {
+        return Err(AppError::BadRequest(format!("Sign-in was refused by the identity provider: {}", error)));
+    }
+
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return Err(AppError::BadRequest("Missing code or state".to_string()));
+    };
+
+    // Each state works once, whatever happens next
+    let login = sqlx::query!(
+        "DELETE FROM sso_login_states WHERE state_hash = $1 RETURNING client_id, code_verifier, nonce, expires_at",
+        hash_token(&state)
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::BadRequest("Unknown or already used sign-in attempt, please start again".to_string()))?;
+
+    if login.expires_at <= Utc::now() {
+        return Err(AppError::BadRequest("Sign-in took too long, please start again".to_string()));
+    }
+
+    let config = sqlx::query_as!(
+        ClientSsoConfig,
+        "SELECT * FROM client_sso_configs WHERE client_id = $1 AND enabled = true",
+        login.client_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let metadata = oidc::discover(&config.issuer).await?;
+    let id_token = oidc::exchange_code(
+        &metadata,
+        &config.oidc_client_id,
+        &config.oidc_client_secret,
+        &sso_redirect_uri(),
+        &code,
+        &login.code_verifier,
+    )
+    .await?;
+
+    let jwks = oidc::fetch_jwks(&metadata).await?;
+    let claims = oidc::verify_id_token(&id_token, &jwks, &config.issuer, &config.oidc_client_id, &login.nonce)?;
+
+    let email = claims
+        .email
+        .clone()
+        .filter(|_| claims.is_email_verified())
+        .ok_or(AppError::BadRequest("The identity provider did not return a verified email".to_string()))?;
+
+    let domain_allowed = config.allowed_email_domains.is_empty()
+        || email_domain(&email).is_some_and(|domain| {
+            config
+                .allowed_email_domains
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(domain))
+        });
+
+    if !domain_allowed {
+        return Err(AppError::Forbidden);
+    }
+
+    let user = find_or_provision_sso_user(&auth_service, &pool, config.client_id, &email).await?;
+
+    let response = auth_service.complete_first_factor(&pool, user, &device).await?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// The client user with this email, created on first sign-in. An email that
+/// belongs to anyone else (another client, a worker, an admin) is refused.
+async fn find_or_provision_sso_user(
+    auth_service: &AuthService,
+    pool: &PgPool,
+    client_id: Uuid,
+    email: &str,
+) -> Result<User, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let existing = sqlx::query_as!(
+        User,
+        "SELECT * FROM users WHERE LOWER(email) = LOWER($1) FOR UPDATE",
+        email
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let user = match existing {
+        Some(user) => {
+            if user.role != "client" || user.client_id != Some(client_id) {
+                return Err(AppError::Forbidden);
+            }
+
+            if !user.is_active {
+                return Err(AppError::Unauthorized);
+            }
+
+            sqlx::query_as!(
+                User,
+                "UPDATE users SET email_verified_at = COALESCE(email_verified_at, NOW()) WHERE id = $1 RETURNING *",
+                user.id
+            )
+            .fetch_one(&mut *tx)
+            .await?
+        }
+        None => {
+            // Nobody knows this password; the account signs in through the provider
+            let password_hash = auth_service.hash_password(&oidc::random_token()).await?;
+
+            tracing::info!("Provisioning SSO user {} for client {}", email, client_id);
+
+            sqlx::query_as!(
+                User,
+                r#"
+                INSERT INTO users (id, email, password_hash, role, client_id, team_role, email_verified_at)
+                VALUES ($1, $2, $3, 'client', $4, 'viewer', NOW())
+                RETURNING *
+                "#,
+                Uuid::new_v4(),
+                email.to_lowercase(),
+                password_hash,
+                client_id
+            )
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    tx.commit().await?;
+
+    Ok(user)
+}
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::auth::{hash_token, invalidate_sessions, new_opaque_token, CurrentUser};
+use crate::error::AppError;
+use crate::mailer::{deliver, Email, Mailer};
+use crate::models::*;
+use crate::permissions::{ensure_other_owner, TeamRole};
+use crate::utils::{app_base_url, validate_email, ApiResponse};
+
+const INVITATION_TTL_DAYS: i64 = 7;
+
+fn parse_team_role(team_role: &str) -> Result<TeamRole, AppError> {
+    TeamRole::parse(team_role).ok_or_else(|| {
+        AppError::BadRequest("Invalid team role. Must be one of: owner, recruiter, hiring_manager, viewer".to_string())
+    })
+}
+
+pub async fn get_team_members(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let members = sqlx::query_as!(
+        TeamMember,
+        r#"
+        SELECT id as user_id, email, team_role, is_active as "is_active!", created_at as "created_at!"
+        FROM users
+        WHERE client_id = $1 AND role = 'client'
+        ORDER BY created_at ASC
+        "#,
+        client_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(members)))
+}
+
+/// Changes a member's team role. They are signed out so their next token carries it.
+pub async fn update_team_member(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((client_id, user_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateTeamMemberRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+    let team_role = parse_team_role(&request.team_role)?;
+
+    let mut tx = pool.begin().await?;
+
+    if team_role != TeamRole::Owner {
+        ensure_other_owner(&mut tx, client_id, user_id).await?;
+    }
+
+    let member = sqlx::query_as!(
+        TeamMember,
+        r#"
+        UPDATE users SET team_role = $3, updated_at = NOW()
+        WHERE id = $1 AND client_id = $2 AND role = 'client'
+        RETURNING id as user_id, email, team_role, is_active as "is_active!", created_at as "created_at!"
+        "#,
+        user_id,
+        client_id,
+        team_role.as_str()
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    // Assignments only matter to hiring managers
+    if team_role != TeamRole::HiringManager {
+        sqlx::query!("DELETE FROM job_assignments WHERE user_id = $1", user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    invalidate_sessions(&mut tx, user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(member)))
+}
+
+/// Removes a member from the organization: the account is deactivated and
+/// signed out everywhere. API keys they created stop working with it.
+pub async fn remove_team_member(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((client_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let mut tx = pool.begin().await?;
+    ensure_other_owner(&mut tx, client_id, user_id).await?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE users SET is_active = false, updated_at = NOW()
+        WHERE id = $1 AND client_id = $2 AND role = 'client' AND is_active = true
+        "#,
+        user_id,
+        client_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    sqlx::query!("DELETE FROM job_assignments WHERE user_id = $1", user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    invalidate_sessions(&mut tx, user_id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"removed": true}))))
+}
+
+pub async fn get_invitations(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let invitations = sqlx::query_as!(
+        ClientInvitation,
+        r#"
+        SELECT id, client_id, email, team_role, invited_by, expires_at, accepted_at, created_at
+        FROM client_invitations
+        WHERE client_id = $1 AND accepted_at IS NULL AND expires_at > NOW()
+        ORDER BY created_at DESC
+        "#,
+        client_id
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(ApiResponse::success(invitations)))
+}
+
+/// Emails an invitation to join the client. Inviting the same address again
+/// replaces the pending invitation.
+pub async fn create_invitation(
+    State(pool): State<PgPool>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    current_user: CurrentUser,
+    Path(client_id): Path<Uuid>,
+    Json(request): Json<CreateInvitationRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let email = request.email.trim().to_lowercase();
+    if !validate_email(&email) {
+        return Err(AppError::BadRequest("Invalid email format".to_string()));
+    }
+
+    let team_role = parse_team_role(&request.team_role)?;
+
+    let company_name = sqlx::query_scalar!(
+        "SELECT company_name FROM clients WHERE id = $1",
+        client_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    let existing_user = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM users WHERE LOWER(email) = $1",
+        email
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if existing_user.unwrap_or(0) > 0 {
+        return Err(AppError::Conflict("A user with this email already exists".to_string()));
+    }
+
+    let token = new_opaque_token();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM client_invitations WHERE client_id = $1 AND LOWER(email) = $2 AND accepted_at IS NULL",
+        client_id,
+        email
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let invitation = sqlx::query_as!(
+        ClientInvitation,
+        r#"
+        INSERT INTO client_invitations (id, client_id, email, team_role, token_hash, invited_by, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, client_id, email, team_role, invited_by, expires_at, accepted_at, created_at
+        "#,
+        Uuid::new_v4(),
+        client_id,
+        email,
+        team_role.as_str(),
+        hash_token(&token),
+        current_user.id,
+        Utc::now() + Duration::days(INVITATION_TTL_DAYS)
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let message = Email {
+        to: email,
+        subject: format!("You're invited to join {}", company_name),
+        body: format!(
+            "{} invited you to join {} on the recruitment platform.\n\
+             Choose a password to accept within {} days:\n{}/accept-invitation?token={}\n\n\
+             If you weren't expecting this, ignore this email.",
+            current_user.email,
+            company_name,
+            INVITATION_TTL_DAYS,
+            app_base_url(),
+            token
+        ),
+    };
+
+    if let Err(e) = deliver(mailer, message).await {
+        tracing::error!("Failed to send invitation email: {:?}", e);
+    }
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(invitation))))
+}
+
+pub async fn revoke_invitation(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path((client_id, id)): Path<(Uuid, Uuid)>,
+) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_client(client_id)?;
+
+    let result = sqlx::query!(
+        "DELETE FROM client_invitations WHERE id = $1 AND client_id = $2 AND accepted_at IS NULL",
+        id,
+        client_id
+    )
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"deleted": true}))))
+}
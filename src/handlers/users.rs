@@ -0,0 +1,449 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::action_tokens::{issue_action_token, TokenPurpose};
+use crate::auth::{invalidate_sessions, AuthService, CurrentUser};
+use crate::error::AppError;
+use crate::impersonation::record_impersonation;
+use crate::mailer::{deliver, Email, Mailer};
+use crate::mfa;
+use crate::models::*;
+use crate::permissions::{ensure_other_owner, Role, TeamRole};
+use crate::throttle::LoginThrottle;
+use crate::utils::{app_base_url, escape_like, ApiResponse, PaginationParams, PaginatedResponse};
+
+#[derive(Debug, Deserialize)]
+pub struct UserFilterQuery {
+    pub search: Option<String>,
+    pub role: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+pub async fn get_users(
+    State(pool): State<PgPool>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filters): Query<UserFilterQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let pagination = PaginationParams::new(pagination.page, pagination.limit);
+    let search = filters.search.map(|search| format!("%{}%", escape_like(search.trim())));
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM users
+        WHERE ($1::varchar IS NULL OR email ILIKE $1)
+          AND ($2::varchar IS NULL OR role = $2)
+          AND ($3::bool IS NULL OR is_active = $3)
+        "#,
+        search,
+        filters.role,
+        filters.is_active
+    )
+    .fetch_one(&pool)
+    .await?
+    .unwrap_or(0);
+
+    let users = sqlx::query_as!(
+        User,
+        r#"
+        SELECT * FROM users
+        WHERE ($1::varchar IS NULL OR email ILIKE $1)
+          AND ($2::varchar IS NULL OR role = $2)
+          AND ($3::bool IS NULL OR is_active = $3)
+        ORDER BY created_at DESC
+        LIMIT $4 OFFSET $5
+        "#,
+        search,
+        filters.role,
+        filters.is_active,
+        pagination.limit(),
+        pagination.offset()
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let response = PaginatedResponse::new(users, pagination.page.unwrap_or(1), pagination.limit.unwrap_or(20), total);
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+pub async fn get_user(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1", id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(user)))
+}
+
+/// Changes a user's role or the client/worker profile the account is linked to.
+/// Tokens carry both, so the user is signed out when either changes.
+pub async fn update_user(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateUserRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1 FOR UPDATE", id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let role = match &request.role {
+        Some(role) => Role::parse(role)
+            .ok_or(AppError::BadRequest("Invalid role. Must be one of: admin, client, worker".to_string()))?,
+        None => Role::parse(&user.role).ok_or(AppError::InternalServerError(format!("Unknown role {}", user.role)))?,
+    };
+
+    // Keeps admins from locking themselves out
+    if id == current_user.id && role != Role::Admin {
+        return Err(AppError::BadRequest("You cannot change your own role".to_string()));
+    }
+
+    if request.team_role.is_some() && role != Role::Client {
+        return Err(AppError::BadRequest("Only client users have a team role".to_string()));
+    }
+
+    let (client_id, worker_id, team_role) = match role {
+        Role::Admin => (None, None, None),
+        Role::Client => {
+            let client_id = request.client_id.or(user.client_id)
+                .ok_or(AppError::BadRequest("Client ID is required for client role".to_string()))?;
+
+            let exists = sqlx::query_scalar!("SELECT COUNT(*) FROM clients WHERE id = $1", client_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            if exists.unwrap_or(0) == 0 {
+                return Err(AppError::BadRequest("Client not found".to_string()));
+            }
+
+            // Joining another organization starts with the least access
+            let team_role = match &request.team_role {
+                Some(team_role) => TeamRole::parse(team_role).ok_or_else(|| {
+                    AppError::BadRequest("Invalid team role. Must be one of: owner, recruiter, hiring_manager, viewer".to_string())
+                })?,
+                None if user.client_id == Some(client_id) => user.team_role.as_deref()
+                    .and_then(TeamRole::parse)
+                    .unwrap_or(TeamRole::Viewer),
+                None => TeamRole::Viewer,
+            };
+
+            (Some(client_id), None, Some(team_role.as_str().to_string()))
+        },
+        Role::Worker => {
+            let worker_id = request.worker_id.or(user.worker_id)
+                .ok_or(AppError::BadRequest("Worker ID is required for worker role".to_string()))?;
+
+            let profile = sqlx::query!(
+                r#"
+                SELECT (SELECT COUNT(*) FROM users u WHERE u.worker_id = p.id AND u.id <> $2) as "accounts!"
+                FROM workers p
+                WHERE p.id = $1
+                "#,
+                worker_id,
+                id
+            )
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or(AppError::BadRequest("Worker not found".to_string()))?;
+
+            if profile.accounts > 0 {
+                return Err(AppError::Conflict("This profile already has an account".to_string()));
+            }
+
+            (None, Some(worker_id), None)
+        },
+    };
+
+    let changed = role.as_str() != user.role
+        || client_id != user.client_id
+        || worker_id != user.worker_id
+        || team_role != user.team_role;
+
+    if !changed {
+        return Ok(Json(ApiResponse::success(user)));
+    }
+
+    // Leaving the organization or the owner role must not leave it without an owner
+    if let Some(old_client_id) = user.client_id {
+        let was_owner = user.team_role.as_deref() == Some(TeamRole::Owner.as_str());
+        let stays_owner = client_id == Some(old_client_id) && team_role == user.team_role;
+
+        if was_owner && !stays_owner {
+            ensure_other_owner(&mut tx, old_client_id, id).await?;
+        }
+    }
+
+    let updated = sqlx::query_as!(
+        User,
+        r#"
+        UPDATE users SET role = $2, client_id = $3, worker_id = $4, team_role = $5, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+        id,
+        role.as_str(),
+        client_id,
+        worker_id,
+        team_role
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // Assignments only carry over for a hiring manager staying in the same organization
+    if team_role.as_deref() != Some(TeamRole::HiringManager.as_str()) || client_id != user.client_id {
+        sqlx::query!("DELETE FROM job_assignments WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    invalidate_sessions(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+/// Blocks the account from signing in and ends its sessions. API keys it created stop working.
+/// The last active owner of an organization cannot be deactivated.
+pub async fn deactivate_user(
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    if id == current_user.id {
+        return Err(AppError::BadRequest("You cannot deactivate your own account".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let owner_of = sqlx::query_scalar!(
+        "SELECT client_id FROM users WHERE id = $1 AND team_role = 'owner' AND is_active = true",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .flatten();
+
+    if let Some(client_id) = owner_of {
+        ensure_other_owner(&mut tx, client_id, id).await?;
+    }
+
+    let user = sqlx::query_as!(
+        User,
+        "UPDATE users SET is_active = false, updated_at = NOW() WHERE id = $1 RETURNING *",
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    invalidate_sessions(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(user)))
+}
+
+pub async fn reactivate_user(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_as!(
+        User,
+        "UPDATE users SET is_active = true, updated_at = NOW() WHERE id = $1 RETURNING *",
+        id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(user)))
+}
+
+/// Signs the user out and refuses password logins until they choose a new
+/// password with the link emailed to them.
+pub async fn force_password_reset(
+    State(pool): State<PgPool>,
+    State(mailer): State<Arc<dyn Mailer>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let email = sqlx::query_scalar!(
+        r#"
+        UPDATE users SET password_reset_required = true, updated_at = NOW()
+        WHERE id = $1 AND is_active = true
+        RETURNING email
+        "#,
+        id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    invalidate_sessions(&mut tx, id).await?;
+    let token = issue_action_token(&mut tx, id, TokenPurpose::PasswordReset).await?;
+
+    tx.commit().await?;
+
+    let message = Email {
+        to: email,
+        subject: "Choose a new password".to_string(),
+        body: format!(
+            "An administrator asked you to choose a new password for your account.\n\n\
+             Use this link within {} minutes to set it:\n{}/reset-password?token={}\n\n\
+             Once it expires, request a new link from the sign-in page.",
+            TokenPurpose::PasswordReset.ttl().num_minutes(),
+            app_base_url(),
+            token
+        ),
+    };
+
+    if let Err(e) = deliver(mailer, message).await {
+        tracing::error!("Failed to send password reset email: {:?}", e);
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"password_reset_required": true}))))
+}
+
+/// Lets an admin see the app as a user does: returns an access token for the
+/// user that also names the admin. Admin accounts cannot be impersonated.
+pub async fn impersonate_user(
+    State(auth_service): State<AuthService>,
+    State(pool): State<PgPool>,
+    current_user: CurrentUser,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1 AND is_active = true", id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    if user.role == Role::Admin.as_str() {
+        return Err(AppError::BadRequest("Admin accounts cannot be impersonated".to_string()));
+    }
+
+    let (token, token_id) = auth_service.issue_impersonation_token(&user, current_user.id)?;
+
+    let mut conn = pool.acquire().await?;
+    record_impersonation(
+        &mut conn,
+        current_user.id,
+        user.id,
+        token_id,
+        "POST",
+        &format!("/api/users/{}/impersonate", user.id),
+        StatusCode::CREATED.as_u16(),
+    )
+    .await?;
+
+    tracing::info!("Admin {} started impersonating user {}", current_user.id, user.id);
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(ImpersonationResponse {
+        token,
+        expires_in: auth_service.access_token_ttl().num_seconds(),
+        user,
+    }))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonationLogQuery {
+    pub admin_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+}
+
+pub async fn get_impersonation_log(
+    State(pool): State<PgPool>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filters): Query<ImpersonationLogQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let pagination = PaginationParams::new(pagination.page, pagination.limit);
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM impersonation_log
+        WHERE ($1::uuid IS NULL OR admin_id = $1)
+          AND ($2::uuid IS NULL OR user_id = $2)
+        "#,
+        filters.admin_id,
+        filters.user_id
+    )
+    .fetch_one(&pool)
+    .await?
+    .unwrap_or(0);
+
+    let entries = sqlx::query_as!(
+        ImpersonationLogEntry,
+        r#"
+        SELECT id, admin_id, user_id, token_id, method, path, status_code, created_at
+        FROM impersonation_log
+        WHERE ($1::uuid IS NULL OR admin_id = $1)
+          AND ($2::uuid IS NULL OR user_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+        filters.admin_id,
+        filters.user_id,
+        pagination.limit(),
+        pagination.offset()
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let response = PaginatedResponse::new(entries, pagination.page.unwrap_or(1), pagination.limit.unwrap_or(20), total);
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Lifts a login lockout or backoff on the user's account before it expires.
+pub async fn unlock_user(
+    State(pool): State<PgPool>,
+    State(throttle): State<LoginThrottle>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let email = sqlx::query_scalar!(
+        "SELECT email FROM users WHERE id = $1",
+        id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    throttle.unlock_account(&email);
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"unlocked": true}))))
+}
+
+/// Removes the authenticator of a user who lost it and has no recovery codes
+/// left. If their role requires MFA, they enroll again at the next login.
+pub async fn reset_user_mfa(
+    State(pool): State<PgPool>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let exists = sqlx::query_scalar!("SELECT COUNT(*) FROM users WHERE id = $1", id)
+        .fetch_one(&pool)
+        .await?;
+
+    if exists.unwrap_or(0) == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    let mut tx = pool.begin().await?;
+    mfa::disable_mfa(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({"mfa_enabled": false}))))
+}
@@ -10,6 +10,8 @@ use uuid::Uuid;
 
 use crate::models::*;
 use crate::error::AppError;
+use crate::auth::CurrentUser;
+use crate::permissions::authorize_worker_profile;
 use crate::utils::{ApiResponse, PaginationParams, PaginatedResponse, validate_email, validate_phone};
 
 #[derive(Debug, Deserialize)]
@@ -98,8 +100,11 @@ pub async fn get_workers(
 
 pub async fn get_worker(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
 ) -> Result<impl IntoResponse, AppError> {
+    authorize_worker_profile(&pool, &current_user, id).await?;
+    
     let worker = sqlx::query_as!(
         Worker,
         r#"
@@ -180,9 +185,12 @@ pub async fn create_worker(
 
 pub async fn update_worker(
     State(pool): State<PgPool>,
+    current_user: CurrentUser,
     Path(id): Path<Uuid>,
     Json(mut request): Json<CreateWorkerRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    current_user.authorize_worker(id)?;
+    
     // Validate input
     if request.name.trim().is_empty() {
         return Err(AppError::BadRequest("Worker name is required".to_string()));
@@ -271,7 +279,7 @@ pub async fn delete_worker(
     
     // Check if worker has active applications
     let active_applications = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM applications WHERE worker_id = $1 AND status IN ('pending', 'reviewing')",
+        "SELECT COUNT(*) FROM applications WHERE worker_id = $1 AND status IN ('pending', 'reviewing', 'interview', 'offer')",
         id
     )
     .fetch_one(&pool)
@@ -281,6 +289,18 @@ pub async fn delete_worker(
         return Err(AppError::BadRequest("Cannot delete worker with active applications".to_string()));
     }
     
+    // Placements are billing records and must outlive the worker
+    let placements = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM placements WHERE worker_id = $1",
+        id
+    )
+    .fetch_one(&pool)
+    .await?;
+    
+    if placements.unwrap_or(0) > 0 {
+        return Err(AppError::BadRequest("Cannot delete worker with placements".to_string()));
+    }
+    
     let result = sqlx::query!(
         "DELETE FROM workers WHERE id = $1",
         id
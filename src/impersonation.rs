@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::auth::Claims;
+use crate::error::AppError;
+
+/// Appends an entry to the impersonation log. `token_id` is the `jti` of the
+/// impersonation token, which groups the requests of one impersonation session.
+pub async fn record_impersonation(
+    conn: &mut PgConnection,
+    admin_id: Uuid,
+    user_id: Uuid,
+    token_id: Uuid,
+    method: &str,
+    path: &str,
+    status_code: u16,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO impersonation_log (id, admin_id, user_id, token_id, method, path, status_code)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        Uuid::new_v4(),
+        admin_id,
+        user_id,
+        token_id,
+        method,
+        path,
+        status_code as i32
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Writes every request made with an impersonation token to the log, with the
+/// status it got. Must run inside `auth_middleware`, which puts the claims on the request.
+pub async fn audit_impersonation(
+    State(pool): State<PgPool>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(claims) = request.extensions().get::<Claims>().cloned() else {
+        return next.run(request).await;
+    };
+
+    let Some(admin_id) = claims.impersonated_by else {
+        return next.run(request).await;
+    };
+
+    let method = request.method().to_string();
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.to_string())
+        .unwrap_or_default();
+
+    let response = next.run(request).await;
+
+    let Ok(user_id) = Uuid::parse_str(&claims.sub) else {
+        return response;
+    };
+
+    let recorded = match pool.acquire().await {
+        Ok(mut conn) => {
+            record_impersonation(&mut conn, admin_id, user_id, claims.jti, &method, &path, response.status().as_u16()).await
+        }
+        Err(e) => Err(e.into()),
+    };
+
+    if let Err(e) = recorded {
+        tracing::error!("Failed to write impersonation log entry: {:?}", e);
+    }
+
+    response
+}
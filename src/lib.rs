@@ -1,7 +1,20 @@
 pub mod models;
 pub mod handlers;
 pub mod database;
+pub mod migrations;
 pub mod auth;
+pub mod permissions;
+pub mod throttle;
+pub mod passwords;
+pub mod mailer;
+pub mod action_tokens;
+pub mod totp;
+pub mod mfa;
+pub mod api_keys;
+pub mod signing_keys;
+pub mod oidc;
+pub mod webauthn;
+pub mod impersonation;
 pub mod error;
 pub mod utils;
 
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Outgoing mail delivery. Swap the implementation without touching handlers.
+/// `send` may block on file or network I/O, so async code goes through `deliver`.
+pub trait Mailer: Send + Sync {
+    fn send(&self, email: &Email) -> Result<(), AppError>;
+}
+
+/// Sends `email` on the blocking pool so a slow mailer doesn't hold up the async workers.
+pub async fn deliver(mailer: Arc<dyn Mailer>, email: Email) -> Result<(), AppError> {
+    tokio::task::spawn_blocking(move || mailer.send(&email))
+        .await
+        .map_err(|e| AppError::InternalServerError(format!("Sending email failed: {}", e)))?
+}
+
+/// Writes every message to a file in `dir`, for local development and tests.
+pub struct OutboxMailer {
+    dir: PathBuf,
+}
+
+impl OutboxMailer {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Mailer for OutboxMailer {
+    fn send(&self, email: &Email) -> Result<(), AppError> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to create mail outbox: {}", e)))?;
+
+        // Timestamp first so a directory listing is in sending order
+        let path = self.dir.join(format!("{}-{}.eml", Utc::now().format("%Y%m%dT%H%M%S%.6f"), Uuid::new_v4()));
+        let contents = format!("To: {}\nSubject: {}\n\n{}\n", email.to, email.subject, email.body);
+
+        fs::write(&path, contents)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to write {}: {}", path.display(), e)))
+    }
+}
+
+/// Logs the recipient and subject of each message instead of sending it, for
+/// development without an outbox. Bodies carry login and reset tokens, so they are never logged.
+pub struct LogMailer;
+
+impl Mailer for LogMailer {
+    fn send(&self, email: &Email) -> Result<(), AppError> {
+        tracing::info!("Mail to {}: {} (body not logged; set MAIL_OUTBOX_DIR to read it)", email.to, email.subject);
+        Ok(())
+    }
+}
+
+/// Picks the mailer from `MAIL_OUTBOX_DIR`. Without one, development falls back
+/// to logging and anywhere else there is no mailer to start with.
+pub fn mailer_from_env(development: bool) -> Option<Arc<dyn Mailer>> {
+    match std::env::var("MAIL_OUTBOX_DIR") {
+        Ok(dir) if !dir.trim().is_empty() => Some(Arc::new(OutboxMailer::new(dir))),
+        _ if development => Some(Arc::new(LogMailer)),
+        _ => None,
+    }
+}
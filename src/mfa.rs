@@ -0,0 +1,201 @@
+use chrono::Utc;
+use rand::RngCore;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::auth::hash_token;
+use crate::error::AppError;
+use crate::totp;
+
+/// Recovery codes handed out when MFA is enabled or the codes are regenerated.
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct MfaSettings {
+    pub secret: String,
+    /// `false` while the enrollment waits for its first code
+    pub enabled: bool,
+    pub last_used_step: Option<i64>,
+}
+
+pub async fn mfa_settings(conn: &mut PgConnection, user_id: Uuid) -> Result<Option<MfaSettings>, AppError> {
+    let settings = sqlx::query!(
+        "SELECT secret, enabled_at, last_used_step FROM user_mfa WHERE user_id = $1",
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .map(|row| MfaSettings {
+        secret: row.secret,
+        enabled: row.enabled_at.is_some(),
+        last_used_step: row.last_used_step,
+    });
+
+    Ok(settings)
+}
+
+/// Whether an admin requires accounts with `role` to use MFA.
+pub async fn role_requires_mfa(conn: &mut PgConnection, role: &str) -> Result<bool, AppError> {
+    let required = sqlx::query_scalar!(
+        "SELECT require_mfa FROM mfa_role_policies WHERE role = $1",
+        role
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(required.unwrap_or(false))
+}
+
+/// Stores a fresh secret awaiting confirmation, replacing an unconfirmed one.
+/// Accounts that already use MFA must disable it first.
+pub async fn start_enrollment(conn: &mut PgConnection, user_id: Uuid) -> Result<String, AppError> {
+    if mfa_settings(conn, user_id).await?.is_some_and(|settings| settings.enabled) {
+        return Err(AppError::Conflict("Two-factor authentication is already enabled".to_string()));
+    }
+
+    let secret = totp::generate_secret();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO user_mfa (user_id, secret)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET secret = EXCLUDED.secret, last_used_step = NULL, created_at = NOW()
+        "#,
+        user_id,
+        secret
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(secret)
+}
+
+/// Checks an authenticator code, enabled or pending, and remembers its time
+/// step so the same code cannot be replayed.
+pub async fn check_totp(conn: &mut PgConnection, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+    let settings = sqlx::query!(
+        "SELECT secret, last_used_step FROM user_mfa WHERE user_id = $1 FOR UPDATE",
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(settings) = settings else {
+        return Ok(false);
+    };
+
+    let Some(step) = totp::verify(&settings.secret, code, Utc::now().timestamp(), settings.last_used_step) else {
+        return Ok(false);
+    };
+
+    sqlx::query!(
+        "UPDATE user_mfa SET last_used_step = $1 WHERE user_id = $2",
+        step,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(true)
+}
+
+/// Spends one recovery code. Each works once.
+pub async fn use_recovery_code(conn: &mut PgConnection, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+    let used = sqlx::query_scalar!(
+        r#"
+        UPDATE mfa_recovery_codes
+        SET used_at = NOW()
+        WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+        RETURNING id
+        "#,
+        user_id,
+        hash_token(&normalize_recovery_code(code))
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(used.is_some())
+}
+
+/// A current authenticator code or an unused recovery code.
+pub async fn check_second_factor(conn: &mut PgConnection, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+    if check_totp(conn, user_id, code).await? {
+        return Ok(true);
+    }
+
+    use_recovery_code(conn, user_id, code).await
+}
+
+/// Turns on a confirmed enrollment and returns its first recovery codes.
+pub async fn enable_mfa(conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<String>, AppError> {
+    sqlx::query!(
+        "UPDATE user_mfa SET enabled_at = NOW() WHERE user_id = $1",
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    replace_recovery_codes(conn, user_id).await
+}
+
+/// Issues a new set of recovery codes; the previous ones stop working.
+pub async fn replace_recovery_codes(conn: &mut PgConnection, user_id: Uuid) -> Result<Vec<String>, AppError> {
+    sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT).map(|_| new_recovery_code()).collect();
+
+    for code in &codes {
+        sqlx::query!(
+            "INSERT INTO mfa_recovery_codes (id, user_id, code_hash) VALUES ($1, $2, $3)",
+            Uuid::new_v4(),
+            user_id,
+            hash_token(&normalize_recovery_code(code))
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(codes)
+}
+
+pub async fn remaining_recovery_codes(conn: &mut PgConnection, user_id: Uuid) -> Result<i64, AppError> {
+    let remaining = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM mfa_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(remaining.unwrap_or(0))
+}
+
+/// Removes the authenticator and recovery codes of a user.
+pub async fn disable_mfa(conn: &mut PgConnection, user_id: Uuid) -> Result<(), AppError> {
+    sqlx::query!("DELETE FROM mfa_recovery_codes WHERE user_id = $1", user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query!("DELETE FROM user_mfa WHERE user_id = $1", user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+// Ten hex digits shown as two groups, e.g. 3f9a1-c07e2
+fn new_recovery_code() -> String {
+    let mut bytes = [0u8; 5];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let digits = hex::encode(bytes);
+    format!("{}-{}", &digits[..5], &digits[5..])
+}
+
+// Recovery codes are compared without separators or case
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Executor, FromRow, PgConnection, PgPool};
+use std::fmt;
+
+/// A schema change from `migrations/`, embedded in the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+/// Every migration, oldest first. Add a new pair of files with the next version
+/// rather than editing a released one: applied migrations are checksummed.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        up: include_str!("../migrations/0001_initial_schema.up.sql"),
+        down: include_str!("../migrations/0001_initial_schema.down.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "session_generation",
+        up: include_str!("../migrations/0002_session_generation.up.sql"),
+        down: include_str!("../migrations/0002_session_generation.down.sql"),
+    },
+];
+
+// Instances starting together take turns; the value only has to be unique to this app
+const MIGRATION_LOCK_KEY: i64 = 0x7265_6372_7569_7465;
+
+/// A row of `schema_migrations`.
+#[derive(Debug, Clone, FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Database(sqlx::Error),
+    /// An applied migration was edited after it ran
+    ChecksumMismatch { version: i64, name: String },
+    /// The database was migrated by a newer build
+    Unknown { version: i64, name: String },
+    NoSuchVersion(i64),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Database(err) => write!(f, "migration failed: {}", err),
+            MigrationError::ChecksumMismatch { version, name } => write!(
+                f,
+                "migration {} ({}) was changed after it was applied; add a new migration instead",
+                version, name
+            ),
+            MigrationError::Unknown { version, name } => write!(
+                f,
+                "database has migration {} ({}) that this build doesn't know; it was migrated by a newer version",
+                version, name
+            ),
+            MigrationError::NoSuchVersion(version) => write!(f, "there is no migration {}", version),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<sqlx::Error> for MigrationError {
+    fn from(err: sqlx::Error) -> Self {
+        MigrationError::Database(err)
+    }
+}
+
+/// SHA-256 of a migration's `up` script, hex encoded.
+pub fn checksum(sql: &str) -> String {
+    hex::encode(Sha256::digest(sql.as_bytes()))
+}
+
+/// Checks that every applied migration is known and unchanged.
+pub fn verify(known: &[Migration], applied: &[AppliedMigration]) -> Result<(), MigrationError> {
+    for row in applied {
+        let migration = known
+            .iter()
+            .find(|migration| migration.version == row.version)
+            .ok_or_else(|| MigrationError::Unknown {
+                version: row.version,
+                name: row.name.clone(),
+            })?;
+
+        if checksum(migration.up) != row.checksum {
+            return Err(MigrationError::ChecksumMismatch {
+                version: row.version,
+                name: row.name.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Migrations still to apply, oldest first, up to `target` when given.
+pub fn pending<'a>(
+    known: &'a [Migration],
+    applied: &[AppliedMigration],
+    target: Option<i64>,
+) -> Result<Vec<&'a Migration>, MigrationError> {
+    verify(known, applied)?;
+
+    if let Some(target) = target {
+        if !known.iter().any(|migration| migration.version == target) {
+            return Err(MigrationError::NoSuchVersion(target));
+        }
+    }
+
+    Ok(known
+        .iter()
+        .filter(|migration| match target {
+            Some(target) => migration.version <= target,
+            None => true,
+        })
+        .filter(|migration| !applied.iter().any(|row| row.version == migration.version))
+        .collect())
+}
+
+/// The latest `steps` applied migrations, newest first.
+pub fn to_revert<'a>(
+    known: &'a [Migration],
+    applied: &[AppliedMigration],
+    steps: usize,
+) -> Result<Vec<&'a Migration>, MigrationError> {
+    verify(known, applied)?;
+
+    let mut versions: Vec<i64> = applied.iter().map(|row| row.version).collect();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+
+    Ok(versions
+        .into_iter()
+        .take(steps)
+        .filter_map(|version| known.iter().find(|migration| migration.version == version))
+        .collect())
+}
+
+/// Every known migration with when it was applied, oldest first.
+pub fn status(known: &[Migration], applied: &[AppliedMigration]) -> Vec<MigrationStatus> {
+    known
+        .iter()
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: applied
+                .iter()
+                .find(|row| row.version == migration.version)
+                .map(|row| row.applied_at),
+        })
+        .collect()
+}
+
+async fn applied_migrations(conn: &mut PgConnection) -> Result<Vec<AppliedMigration>, MigrationError> {
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version BIGINT PRIMARY KEY,
+            name VARCHAR(255) NOT NULL,
+            checksum VARCHAR(64) NOT NULL,
+            applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .await?;
+
+    let applied = sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version",
+    )
+    .fetch_all(conn)
+    .await?;
+
+    Ok(applied)
+}
+
+async fn apply(conn: &mut PgConnection, target: Option<i64>) -> Result<Vec<&'static Migration>, MigrationError> {
+    let applied = applied_migrations(conn).await?;
+    let pending = pending(MIGRATIONS, &applied, target)?;
+
+    // One transaction per migration, so a failure keeps the ones before it
+    for migration in &pending {
+        let mut tx = conn.begin().await?;
+
+        tx.execute(migration.up).await?;
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(checksum(migration.up))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        tracing::info!("Applied migration {} ({})", migration.version, migration.name);
+    }
+
+    Ok(pending)
+}
+
+async fn revert(conn: &mut PgConnection, steps: usize) -> Result<Vec<&'static Migration>, MigrationError> {
+    let applied = applied_migrations(conn).await?;
+    let reverted = to_revert(MIGRATIONS, &applied, steps)?;
+
+    for migration in &reverted {
+        let mut tx = conn.begin().await?;
+
+        tx.execute(migration.down).await?;
+        sqlx::query("DELETE FROM schema_migrations WHERE version = $1")
+            .bind(migration.version)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        tracing::info!("Reverted migration {} ({})", migration.version, migration.name);
+    }
+
+    Ok(reverted)
+}
+
+/// Applies pending migrations, up to `target` when given, and returns them.
+pub async fn migrate_up(pool: &PgPool, target: Option<i64>) -> Result<Vec<&'static Migration>, MigrationError> {
+    let mut conn = pool.acquire().await?;
+
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = apply(&mut conn, target).await;
+
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    result
+}
+
+/// Reverts the latest `steps` migrations and returns them.
+pub async fn migrate_down(pool: &PgPool, steps: usize) -> Result<Vec<&'static Migration>, MigrationError> {
+    let mut conn = pool.acquire().await?;
+
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = revert(&mut conn, steps).await;
+
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    result
+}
+
+pub async fn migration_status(pool: &PgPool) -> Result<Vec<MigrationStatus>, MigrationError> {
+    let mut conn = pool.acquire().await?;
+    let applied = applied_migrations(&mut conn).await?;
+    verify(MIGRATIONS, &applied)?;
+
+    Ok(status(MIGRATIONS, &applied))
+}
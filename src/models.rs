@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use crate::error::AppError;
+use crate::webauthn::{AssertionCredential, RegistrationCredential};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Client {
@@ -37,21 +39,338 @@ pub struct JobPosting {
     pub location: String,
     pub job_type: String,
     pub is_active: bool,
+    pub headcount: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// Job posting as shown to anonymous visitors: active jobs only, without internal fields
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct PublicJobPosting {
+    pub id: Uuid,
+    pub company_name: String,
+    pub title: String,
+    pub description: String,
+    pub requirements: Vec<String>,
+    pub salary_range: Option<String>,
+    pub location: String,
+    pub job_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ApplicationStatus {
+    Pending,
+    Reviewing,
+    Interview,
+    Offer,
+    Hired,
+    Rejected,
+    Withdrawn,
+}
+
+impl ApplicationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationStatus::Pending => "pending",
+            ApplicationStatus::Reviewing => "reviewing",
+            ApplicationStatus::Interview => "interview",
+            ApplicationStatus::Offer => "offer",
+            ApplicationStatus::Hired => "hired",
+            ApplicationStatus::Rejected => "rejected",
+            ApplicationStatus::Withdrawn => "withdrawn",
+        }
+    }
+
+    /// Legal next statuses. Every open status can still end in rejected or withdrawn.
+    pub fn allowed_transitions(&self) -> &'static [ApplicationStatus] {
+        use ApplicationStatus::*;
+        match self {
+            Pending => &[Reviewing, Rejected, Withdrawn],
+            Reviewing => &[Interview, Rejected, Withdrawn],
+            Interview => &[Offer, Rejected, Withdrawn],
+            Offer => &[Hired, Rejected, Withdrawn],
+            Hired | Rejected | Withdrawn => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: ApplicationStatus) -> bool {
+        self.allowed_transitions().contains(&next)
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.allowed_transitions().is_empty()
+    }
+
+    /// The statuses to pass through, in order, to reach `target`: forward along
+    /// pending -> reviewing -> interview -> offer -> hired, or straight to rejected or
+    /// withdrawn. `None` when `target` is behind this status or cannot be reached.
+    pub fn path_to(&self, target: ApplicationStatus) -> Option<Vec<ApplicationStatus>> {
+        use ApplicationStatus::*;
+        const PATH: [ApplicationStatus; 5] = [Pending, Reviewing, Interview, Offer, Hired];
+
+        if *self == target {
+            return Some(Vec::new());
+        }
+
+        if !PATH.contains(&target) {
+            return self.can_transition_to(target).then(|| vec![target]);
+        }
+
+        let from = PATH.iter().position(|status| status == self)?;
+        let to = PATH.iter().position(|status| *status == target)?;
+        (from < to).then(|| PATH[from + 1..=to].to_vec())
+    }
+}
+
+impl std::fmt::Display for ApplicationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Application {
     pub id: Uuid,
     pub job_id: Uuid,
     pub worker_id: Uuid,
-    pub status: String,
+    pub status: ApplicationStatus,
+    pub stage_id: Option<Uuid>,
+    pub stage_entered_at: Option<DateTime<Utc>>,
     pub cover_letter: Option<String>,
     pub applied_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PipelineStage {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub name: String,
+    pub position: i32,
+    pub is_terminal: bool,
+    pub is_success: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ApplicationStatusHistory {
+    pub id: Uuid,
+    pub application_id: Uuid,
+    pub from_status: Option<ApplicationStatus>,
+    pub to_status: ApplicationStatus,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_email: Option<String>,
+    pub reason: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OfferStatus {
+    Draft,
+    Sent,
+    Accepted,
+    Declined,
+    Expired,
+    Rescinded,
+}
+
+impl OfferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OfferStatus::Draft => "draft",
+            OfferStatus::Sent => "sent",
+            OfferStatus::Accepted => "accepted",
+            OfferStatus::Declined => "declined",
+            OfferStatus::Expired => "expired",
+            OfferStatus::Rescinded => "rescinded",
+        }
+    }
+
+    pub fn allowed_transitions(&self) -> &'static [OfferStatus] {
+        use OfferStatus::*;
+        match self {
+            Draft => &[Sent, Rescinded],
+            Sent => &[Accepted, Declined, Expired, Rescinded],
+            Accepted | Declined | Expired | Rescinded => &[],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: OfferStatus) -> bool {
+        self.allowed_transitions().contains(&next)
+    }
+
+    /// Draft and sent offers can still have their terms revised
+    pub fn is_open(&self) -> bool {
+        matches!(self, OfferStatus::Draft | OfferStatus::Sent)
+    }
+}
+
+impl std::fmt::Display for OfferStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Offer {
+    pub id: Uuid,
+    pub application_id: Uuid,
+    pub salary: i64,
+    pub currency: String,
+    pub start_date: NaiveDate,
+    pub expires_at: DateTime<Utc>,
+    pub status: OfferStatus,
+    pub notes: Option<String>,
+    pub version: i32,
+    pub created_by: Option<Uuid>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Offer {
+    /// A sent offer whose deadline has passed; the expiry sweep expires these.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.status == OfferStatus::Sent && self.expires_at <= now
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct OfferRevision {
+    pub id: Uuid,
+    pub offer_id: Uuid,
+    pub version: i32,
+    pub event: String,
+    pub salary: i64,
+    pub currency: String,
+    pub start_date: NaiveDate,
+    pub expires_at: DateTime<Utc>,
+    pub status: OfferStatus,
+    pub notes: Option<String>,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_email: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Placement {
+    pub id: Uuid,
+    pub application_id: Option<Uuid>,
+    pub worker_id: Uuid,
+    pub job_id: Uuid,
+    pub client_id: Uuid,
+    pub start_date: NaiveDate,
+    pub agreed_salary: i64,
+    pub currency: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum FeeType {
+    Percentage,
+    Flat,
+}
+
+impl FeeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeType::Percentage => "percentage",
+            FeeType::Flat => "flat",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FeeAgreement {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub fee_type: FeeType,
+    /// Share of first-year salary in basis points (2000 = 20%)
+    pub percentage_bps: Option<i32>,
+    pub flat_fee: Option<i64>,
+    pub currency: String,
+    pub payment_terms_days: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl FeeAgreement {
+    /// Agency fee owed for a placement at the given first-year salary, rounded half up
+    /// to whole currency units. Fails rather than wrapping when the salary is too large.
+    pub fn fee_for_salary(&self, agreed_salary: i64) -> Result<i64, AppError> {
+        let missing = || AppError::InternalServerError("Fee agreement is missing its amount".to_string());
+
+        match self.fee_type {
+            FeeType::Percentage => {
+                let bps = self.percentage_bps.ok_or_else(missing)? as i64;
+                agreed_salary
+                    .checked_mul(bps)
+                    .and_then(|fee| fee.checked_add(5_000))
+                    .map(|fee| fee / 10_000)
+                    .ok_or(AppError::BadRequest("Salary is too large to compute a fee".to_string()))
+            }
+            FeeType::Flat => self.flat_fee.ok_or_else(missing),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceStatus {
+    Unpaid,
+    Paid,
+}
+
+impl InvoiceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InvoiceStatus::Unpaid => "unpaid",
+            InvoiceStatus::Paid => "paid",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Invoice {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub client_id: Uuid,
+    pub placement_id: Option<Uuid>,
+    pub issue_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub currency: String,
+    pub subtotal: i64,
+    pub total: i64,
+    pub status: InvoiceStatus,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct InvoiceLineItem {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    pub position: i32,
+    pub description: String,
+    pub quantity: i32,
+    pub unit_amount: i64,
+    pub amount: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Meeting {
     pub id: Uuid,
@@ -73,6 +392,7 @@ pub struct Meeting {
 pub struct User {
     pub id: Uuid,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password_hash: String,
     pub role: String,
     pub client_id: Option<Uuid>,
@@ -80,6 +400,12 @@ pub struct User {
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    pub team_role: Option<String>, // client users: owner, recruiter, hiring_manager or viewer
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub password_reset_required: bool,
+    #[serde(skip_serializing)]
+    pub session_generation: i32, // bumped by `invalidate_sessions`
 }
 
 // Request DTOs
@@ -110,6 +436,87 @@ pub struct CreateJobRequest {
     pub location: String,
     pub job_type: Option<String>,
     pub is_active: Option<bool>,
+    pub headcount: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApplicationRequest {
+    pub cover_letter: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateApplicationStatusRequest {
+    pub status: ApplicationStatus,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PipelineStageInput {
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub is_terminal: Option<bool>,
+    pub is_success: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdatePipelineRequest {
+    pub stages: Vec<PipelineStageInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveApplicationStageRequest {
+    pub stage_id: Uuid,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BoardMove {
+    pub application_id: Uuid,
+    pub stage_id: Uuid,
+    pub position: i32,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBoardRequest {
+    pub moves: Vec<BoardMove>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OfferTermsRequest {
+    pub salary: i64,
+    pub currency: Option<String>,
+    pub start_date: NaiveDate,
+    pub expires_at: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OfferActionRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePlacementRequest {
+    pub application_id: Uuid,
+    pub start_date: NaiveDate,
+    pub agreed_salary: i64,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeAgreementRequest {
+    pub fee_type: FeeType,
+    pub percentage_bps: Option<i32>,
+    pub flat_fee: Option<i64>,
+    pub currency: Option<String>,
+    pub payment_terms_days: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateInvoiceStatusRequest {
+    pub status: InvoiceStatus,
 }
 
 #[derive(Debug, Deserialize)]
@@ -134,6 +541,15 @@ pub struct CreateUserRequest {
     pub worker_id: Option<Uuid>,
 }
 
+/// Admin changes to an account. A new role drops links the role doesn't use.
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    pub role: Option<String>,
+    pub client_id: Option<Uuid>,
+    pub worker_id: Option<Uuid>,
+    pub team_role: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     pub email: String,
@@ -143,9 +559,301 @@ pub struct LoginRequest {
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
     pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64, // access token lifetime in seconds
     pub user: User,
 }
 
+/// Answer to a correct password: a session, or a challenge for the second factor.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResult {
+    Session(LoginResponse),
+    MfaRequired(MfaChallengeResponse),
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    pub mfa_setup_required: bool, // role requires MFA but the user has not enrolled yet
+    pub mfa_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MfaVerifyRequest {
+    pub mfa_token: String,
+    pub code: String, // authenticator code or recovery code
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MfaChallengeSetupRequest {
+    pub mfa_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaSetupResponse {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaSessionResponse {
+    #[serde(flatten)]
+    pub session: LoginResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>, // only when this login completed enrollment
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    pub required_by_role: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MfaRolePolicy {
+    pub role: String,
+    pub require_mfa: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMfaPolicyRequest {
+    pub require_mfa: bool,
+}
+
+/// Token issued to an admin for acting as another user.
+#[derive(Debug, Serialize)]
+pub struct ImpersonationResponse {
+    pub token: String,
+    pub expires_in: i64, // seconds; there is no refresh token
+    pub user: User,
+}
+
+/// A request made by an admin while impersonating a user.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ImpersonationLogEntry {
+    pub id: Uuid,
+    pub admin_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub token_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub status_code: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A passkey as listed; the public key stays in the database.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Passkey {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinishPasskeyRegistrationRequest {
+    pub name: Option<String>,
+    pub credential: RegistrationCredential,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FinishPasskeyLoginRequest {
+    pub credential: AssertionCredential,
+}
+
+/// A client API key as listed; the key itself is only shown once, at creation.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateApiKeyRequest {
+    pub name: Option<String>,
+    pub scopes: Option<Vec<String>>,
+    /// Absent keeps the expiry, `null` removes it.
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub expires_at: Option<Option<DateTime<Utc>>>,
+}
+
+// Lets a field tell "absent" (None, via `default`) from an explicit `null` (Some(None))
+fn explicit_null<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+/// A user of a client organization.
+#[derive(Debug, Serialize, FromRow)]
+pub struct TeamMember {
+    pub user_id: Uuid,
+    pub email: String,
+    pub team_role: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateTeamMemberRequest {
+    pub team_role: String,
+}
+
+/// A pending or accepted invitation to join a client organization.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ClientInvitation {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub email: String,
+    pub team_role: String,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateInvitationRequest {
+    pub email: String,
+    pub team_role: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct JobAssignment {
+    pub job_id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub assigned_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignJobRequest {
+    pub user_id: Uuid,
+}
+
+/// A client's OpenID Connect provider for single sign-on.
+#[derive(Debug, Serialize, FromRow)]
+pub struct ClientSsoConfig {
+    pub client_id: Uuid,
+    pub issuer: String,
+    pub oidc_client_id: String,
+    #[serde(skip_serializing)]
+    pub oidc_client_secret: String,
+    pub allowed_email_domains: Vec<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertSsoConfigRequest {
+    pub issuer: String,
+    pub oidc_client_id: String,
+    pub oidc_client_secret: String,
+    pub allowed_email_domains: Option<Vec<String>>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SsoCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogoutRequest {
+    pub refresh_token: Option<String>,
+}
+
+/// A login of the user on some device, from sign-in until logout or revocation.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub current: bool, // the session of the token used for the request
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkLoginRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmPasswordResetRequest {
+    pub token: String,
+    pub new_password: String,
+    pub confirm_password: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct JobMatchResponse {
     pub job: JobPosting,
@@ -160,3 +868,42 @@ pub struct WorkerMatchScore {
     pub score: f64,
     pub matching_skills: Vec<String>,
 }
+
+#[derive(Debug, Serialize)]
+pub struct PipelineBoard {
+    pub job_id: Uuid,
+    pub total: usize,
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardColumn {
+    pub stage: PipelineStage,
+    pub count: usize,
+    pub cards: Vec<BoardCard>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardCard {
+    pub application_id: Uuid,
+    pub status: ApplicationStatus,
+    pub position: i32,
+    pub days_in_stage: i64,
+    pub applied_at: DateTime<Utc>,
+    pub worker: BoardWorkerSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardWorkerSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub top_matching_skills: Vec<String>,
+    pub match_score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvoiceWithLineItems {
+    #[serde(flatten)]
+    pub invoice: Invoice,
+    pub line_items: Vec<InvoiceLineItem>,
+}
@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use reqwest::Url;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+/// Endpoints from the provider's `/.well-known/openid-configuration`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    // Booleans by the spec, but some providers send "true"
+    email_verified: Option<serde_json::Value>,
+    pub nonce: Option<String>,
+}
+
+impl IdTokenClaims {
+    pub fn is_email_verified(&self) -> bool {
+        match &self.email_verified {
+            Some(serde_json::Value::Bool(verified)) => *verified,
+            Some(serde_json::Value::String(verified)) => verified == "true",
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+// Asymmetric algorithms only; an HS256 token would be checked against a public key
+const ALLOWED_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+/// Random URL-safe value for `state`, `nonce` and the PKCE verifier.
+pub fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE64URL_NOPAD.encode(&bytes)
+}
+
+/// RFC 7636 `S256` code challenge for a verifier.
+pub fn pkce_challenge(verifier: &str) -> String {
+    BASE64URL_NOPAD.encode(&Sha256::digest(verifier.as_bytes()))
+}
+
+/// Where to send the browser to sign in at the provider.
+pub fn authorization_url(
+    metadata: &ProviderMetadata,
+    client_id: &str,
+    redirect_uri: &str,
+    state: &str,
+    nonce: &str,
+    code_verifier: &str,
+) -> Result<String, AppError> {
+    let url = Url::parse_with_params(
+        &metadata.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", client_id),
+            ("redirect_uri", redirect_uri),
+            ("scope", "openid email profile"),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", &pkce_challenge(code_verifier)),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Invalid authorization endpoint: {}", e)))?;
+
+    Ok(url.to_string())
+}
+
+fn http_client() -> Result<reqwest::Client, AppError> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| AppError::InternalServerError(format!("Failed to build HTTP client: {}", e)))
+}
+
+fn provider_error(step: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::InternalServerError(format!("Identity provider {} failed: {}", step, e))
+}
+
+/// Fetches the discovery document and checks it belongs to `issuer`.
+pub async fn discover(issuer: &str) -> Result<ProviderMetadata, AppError> {
+    let issuer = issuer.trim_end_matches('/');
+    let metadata: ProviderMetadata = http_client()?
+        .get(format!("{}/.well-known/openid-configuration", issuer))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| provider_error("discovery", e))?
+        .json()
+        .await
+        .map_err(|e| provider_error("discovery", e))?;
+
+    if metadata.issuer.trim_end_matches('/') != issuer {
+        return Err(provider_error("discovery", format!("issuer mismatch: {}", metadata.issuer)));
+    }
+
+    Ok(metadata)
+}
+
+/// Redeems an authorization code with the PKCE verifier and returns the ID token.
+pub async fn exchange_code(
+    metadata: &ProviderMetadata,
+    client_id: &str,
+    client_secret: &str,
+    redirect_uri: &str,
+    code: &str,
+    code_verifier: &str,
+) -> Result<String, AppError> {
+    let response: TokenResponse = http_client()?
+        .post(&metadata.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("code_verifier", code_verifier),
+        ])
+        .send()
+        .await
+        .map_err(|e| provider_error("token exchange", e))?
+        .error_for_status()
+        // A rejected code is the caller's problem, not ours
+        .map_err(|_| AppError::Unauthorized)?
+        .json()
+        .await
+        .map_err(|e| provider_error("token exchange", e))?;
+
+    Ok(response.id_token)
+}
+
+pub async fn fetch_jwks(metadata: &ProviderMetadata) -> Result<JwkSet, AppError> {
+    http_client()?
+        .get(&metadata.jwks_uri)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| provider_error("JWKS", e))?
+        .json()
+        .await
+        .map_err(|e| provider_error("JWKS", e))
+}
+
+/// Checks the ID token's signature against the provider keys, its issuer,
+/// audience and expiry, and that it answers this login's `nonce`.
+pub fn verify_id_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+) -> Result<IdTokenClaims, AppError> {
+    let header = decode_header(id_token).map_err(|_| AppError::Unauthorized)?;
+
+    if !ALLOWED_ALGORITHMS.contains(&header.alg) {
+        return Err(AppError::Unauthorized);
+    }
+
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or(AppError::Unauthorized)?;
+
+    let key = DecodingKey::from_jwk(jwk).map_err(|_| AppError::Unauthorized)?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[issuer.trim_end_matches('/'), issuer]);
+    validation.set_audience(&[client_id]);
+
+    let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+        .map_err(|_| AppError::Unauthorized)?
+        .claims;
+
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(claims)
+}
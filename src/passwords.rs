@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+
+use argon2::password_hash::{self, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rand::rngs::OsRng;
+
+use crate::error::AppError;
+
+/// Longest password accepted. Argon2 reads the whole password (bcrypt ignored
+/// everything after 72 bytes); the limit only bounds the work per login.
+pub const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// Argon2id cost, set with `ARGON2_MEMORY_KIB`, `ARGON2_ITERATIONS` and `ARGON2_PARALLELISM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Config {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for Argon2Config {
+    // The OWASP recommendation for Argon2id
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl Argon2Config {
+    pub fn params(&self) -> Result<Params, password_hash::Error> {
+        Params::new(self.memory_kib, self.iterations, self.parallelism, None).map_err(Into::into)
+    }
+
+    fn hasher(&self) -> Result<Argon2<'static>, AppError> {
+        let params = self
+            .params()
+            .map_err(|e| AppError::InternalServerError(format!("Invalid Argon2 parameters: {}", e)))?;
+
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+fn is_bcrypt_hash(hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2y$"].iter().any(|prefix| hash.starts_with(prefix))
+}
+
+/// Hashes with Argon2id and a random salt, in the PHC string format.
+pub fn hash_password(config: &Argon2Config, password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    config
+        .hasher()?
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::InternalServerError(format!("Failed to hash password: {}", e)))
+}
+
+/// Checks a password against an Argon2 hash, or a bcrypt hash from before Argon2.
+/// Argon2 hashes carry their own parameters, so changing the configuration
+/// doesn't break existing ones.
+pub fn verify_password(password: &str, hash: &str) -> Result<bool, AppError> {
+    if is_bcrypt_hash(hash) {
+        return bcrypt::verify(password, hash)
+            .map_err(|e| AppError::InternalServerError(format!("Failed to verify password: {}", e)));
+    }
+
+    let parsed = PasswordHash::new(hash)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to parse password hash: {}", e)))?;
+
+    match Argon2::default().verify_password(password.as_bytes(), &parsed) {
+        Ok(()) => Ok(true),
+        Err(password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(AppError::InternalServerError(format!("Failed to verify password: {}", e))),
+    }
+}
+
+/// Whether a hash should be replaced at the next successful login: it is a
+/// bcrypt hash, or Argon2 with another variant or parameters than configured.
+pub fn needs_rehash(config: &Argon2Config, hash: &str) -> bool {
+    if is_bcrypt_hash(hash) {
+        return true;
+    }
+
+    // Unreadable hashes fail verification anyway
+    let Ok(parsed) = PasswordHash::new(hash) else {
+        return false;
+    };
+
+    let Ok(params) = Params::try_from(&parsed) else {
+        return false;
+    };
+
+    parsed.algorithm != Algorithm::Argon2id.ident()
+        || params.m_cost() != config.memory_kib
+        || params.t_cost() != config.iterations
+        || params.p_cost() != config.parallelism
+}
+
+/// Kinds of characters a password policy can require.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterClass {
+    Uppercase,
+    Lowercase,
+    Digit,
+    Special,
+}
+
+impl CharacterClass {
+    pub const ALL: &'static [CharacterClass] = &[
+        CharacterClass::Uppercase,
+        CharacterClass::Lowercase,
+        CharacterClass::Digit,
+        CharacterClass::Special,
+    ];
+
+    /// Values accepted in `PASSWORD_REQUIRED_CLASSES`.
+    pub fn parse(class: &str) -> Option<Self> {
+        match class {
+            "uppercase" => Some(CharacterClass::Uppercase),
+            "lowercase" => Some(CharacterClass::Lowercase),
+            "digit" => Some(CharacterClass::Digit),
+            "special" => Some(CharacterClass::Special),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharacterClass::Uppercase => c.is_uppercase(),
+            CharacterClass::Lowercase => c.is_lowercase(),
+            CharacterClass::Digit => c.is_numeric(),
+            CharacterClass::Special => !c.is_alphanumeric() && !c.is_whitespace(),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            CharacterClass::Uppercase => "one uppercase letter",
+            CharacterClass::Lowercase => "one lowercase letter",
+            CharacterClass::Digit => "one number",
+            CharacterClass::Special => "one special character",
+        }
+    }
+}
+
+/// Parses a comma-separated `PASSWORD_REQUIRED_CLASSES`; an empty list requires none.
+pub fn parse_character_classes(list: &str) -> Option<Vec<CharacterClass>> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|class| !class.is_empty())
+        .map(CharacterClass::parse)
+        .collect()
+}
+
+/// What a new password must satisfy, checked on registration, password changes,
+/// resets and invitation acceptance.
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub required_classes: Vec<CharacterClass>,
+    denylist: HashSet<String>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            required_classes: CharacterClass::ALL.to_vec(),
+            denylist: HashSet::new(),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Refuses the passwords listed in `contents`, one per line, compared without
+    /// case. Blank lines and lines starting with `#` are skipped.
+    pub fn with_denylist(mut self, contents: &str) -> Self {
+        self.denylist = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_lowercase)
+            .collect();
+        self
+    }
+
+    pub fn denylist_len(&self) -> usize {
+        self.denylist.len()
+    }
+
+    pub fn validate(&self, password: &str) -> Result<(), AppError> {
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            return Err(AppError::BadRequest(format!(
+                "Password must be at least {} characters",
+                self.min_length
+            )));
+        }
+
+        if length > MAX_PASSWORD_LENGTH {
+            return Err(AppError::BadRequest(format!(
+                "Password must be at most {} characters",
+                MAX_PASSWORD_LENGTH
+            )));
+        }
+
+        let missing = self
+            .required_classes
+            .iter()
+            .any(|class| !password.chars().any(|c| class.matches(c)));
+
+        if missing {
+            let descriptions: Vec<&str> = self.required_classes.iter().map(CharacterClass::describe).collect();
+            let requirement = match descriptions.split_last() {
+                Some((last, rest)) if !rest.is_empty() => format!("{}, and {}", rest.join(", "), last),
+                _ => descriptions.join(""),
+            };
+
+            return Err(AppError::BadRequest(format!("Password must contain at least {}", requirement)));
+        }
+
+        if self.denylist.contains(&password.to_lowercase()) {
+            return Err(AppError::BadRequest(
+                "This password is too common, choose another one".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,594 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use axum::{
+    extract::Request,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, PgPool};
+use tower::{Layer, Service};
+use uuid::Uuid;
+
+use crate::api_keys::ApiKeyScopes;
+use crate::auth::{Claims, CurrentUser, UnverifiedEmail};
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    Client,
+    Worker,
+}
+
+impl Role {
+    pub fn parse(role: &str) -> Option<Role> {
+        match role {
+            "admin" => Some(Role::Admin),
+            "client" => Some(Role::Client),
+            "worker" => Some(Role::Worker),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Client => "client",
+            Role::Worker => "worker",
+        }
+    }
+
+    /// Permissions granted to the role. Admins are granted everything.
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+
+        match self {
+            Role::Admin => Permission::ALL,
+            Role::Client => &[
+                ViewClients,
+                ViewWorkers,
+                ViewJobs,
+                ManageJobs,
+                ViewApplications,
+                ManageApplications,
+                ViewOffers,
+                ViewBilling,
+                ViewMeetings,
+                ManageMeetings,
+                UseMatching,
+                ManageApiKeys,
+                ManageTeam,
+            ],
+            Role::Worker => &[
+                ViewWorkers,
+                EditWorkers,
+                ViewJobs,
+                ApplyToJobs,
+                ViewApplications,
+                ViewOffers,
+                RespondToOffers,
+                ViewMeetings,
+                UseMatching,
+            ],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+/// What a client user may do within its organization. Narrows the client
+/// role's permissions; it never grants more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamRole {
+    Owner,
+    Recruiter,
+    /// Works only on the jobs they are assigned to
+    HiringManager,
+    Viewer,
+}
+
+impl TeamRole {
+    pub fn parse(team_role: &str) -> Option<TeamRole> {
+        match team_role {
+            "owner" => Some(TeamRole::Owner),
+            "recruiter" => Some(TeamRole::Recruiter),
+            "hiring_manager" => Some(TeamRole::HiringManager),
+            "viewer" => Some(TeamRole::Viewer),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TeamRole::Owner => "owner",
+            TeamRole::Recruiter => "recruiter",
+            TeamRole::HiringManager => "hiring_manager",
+            TeamRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        use Permission::*;
+
+        match self {
+            TeamRole::Owner => Role::Client.permissions(),
+            TeamRole::Recruiter => &[
+                ViewClients,
+                ViewWorkers,
+                ViewJobs,
+                ManageJobs,
+                ViewApplications,
+                ManageApplications,
+                ViewOffers,
+                ViewBilling,
+                ViewMeetings,
+                ManageMeetings,
+                UseMatching,
+            ],
+            TeamRole::HiringManager => &[
+                ViewClients,
+                ViewWorkers,
+                ViewJobs,
+                ViewApplications,
+                ManageApplications,
+                ViewOffers,
+                ViewMeetings,
+                ManageMeetings,
+                UseMatching,
+            ],
+            TeamRole::Viewer => &[
+                ViewClients,
+                ViewWorkers,
+                ViewJobs,
+                ViewApplications,
+                ViewOffers,
+                ViewMeetings,
+            ],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+
+    /// Team role carried by a client token. Tokens without one (issued before
+    /// team roles existed) get the least privilege.
+    pub fn from_claims(claims: &Claims) -> Option<TeamRole> {
+        match Role::parse(&claims.role) {
+            Some(Role::Client) => Some(
+                claims
+                    .team_role
+                    .as_deref()
+                    .and_then(TeamRole::parse)
+                    .unwrap_or(TeamRole::Viewer),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// What a route requires of the caller's role. Whether the caller may touch a
+/// particular record (its own client, jobs or worker profile) is checked by the
+/// handler with the `authorize_*` helpers below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ViewClients,
+    ManageClients,
+    ViewWorkers,
+    EditWorkers,
+    ManageWorkers,
+    ViewJobs,
+    ManageJobs,
+    ApplyToJobs,
+    ViewApplications,
+    ManageApplications,
+    ViewOffers,
+    ManageOffers,
+    RespondToOffers,
+    ViewBilling,
+    ManageBilling,
+    ViewMeetings,
+    ManageMeetings,
+    UseMatching,
+    ViewReports,
+    ManageUsers,
+    ManageApiKeys,
+    ManageTeam,
+}
+
+impl Permission {
+    pub const ALL: &'static [Permission] = &[
+        Permission::ViewClients,
+        Permission::ManageClients,
+        Permission::ViewWorkers,
+        Permission::EditWorkers,
+        Permission::ManageWorkers,
+        Permission::ViewJobs,
+        Permission::ManageJobs,
+        Permission::ApplyToJobs,
+        Permission::ViewApplications,
+        Permission::ManageApplications,
+        Permission::ViewOffers,
+        Permission::ManageOffers,
+        Permission::RespondToOffers,
+        Permission::ViewBilling,
+        Permission::ManageBilling,
+        Permission::ViewMeetings,
+        Permission::ManageMeetings,
+        Permission::UseMatching,
+        Permission::ViewReports,
+        Permission::ManageUsers,
+        Permission::ManageApiKeys,
+        Permission::ManageTeam,
+    ];
+
+    /// Permissions that change data, refused to unverified accounts when
+    /// `EMAIL_VERIFICATION_POLICY=restrict`.
+    pub fn requires_verified_email(&self) -> bool {
+        !matches!(
+            self,
+            Permission::ViewClients
+                | Permission::ViewWorkers
+                | Permission::ViewJobs
+                | Permission::ViewApplications
+                | Permission::ViewOffers
+                | Permission::ViewBilling
+                | Permission::ViewMeetings
+                | Permission::UseMatching
+                | Permission::ViewReports
+        )
+    }
+
+    /// Permissions over credentials and access, refused to impersonation tokens
+    /// so support staff can look around without taking over the account.
+    pub fn allowed_while_impersonating(&self) -> bool {
+        !matches!(
+            self,
+            Permission::ManageUsers | Permission::ManageApiKeys | Permission::ManageTeam
+        )
+    }
+}
+
+/// Scopes a client API key can carry and the permission each one grants. Keys
+/// act with the client role, so every scope maps to a client permission.
+pub const API_KEY_SCOPES: &[(&str, Permission)] = &[
+    ("clients:read", Permission::ViewClients),
+    ("workers:read", Permission::ViewWorkers),
+    ("jobs:read", Permission::ViewJobs),
+    ("jobs:write", Permission::ManageJobs),
+    ("applications:read", Permission::ViewApplications),
+    ("applications:write", Permission::ManageApplications),
+    ("offers:read", Permission::ViewOffers),
+    ("billing:read", Permission::ViewBilling),
+    ("meetings:read", Permission::ViewMeetings),
+    ("meetings:write", Permission::ManageMeetings),
+    ("matching:read", Permission::UseMatching),
+];
+
+pub fn scope_permission(scope: &str) -> Option<Permission> {
+    API_KEY_SCOPES
+        .iter()
+        .find(|(name, _)| *name == scope)
+        .map(|(_, permission)| *permission)
+}
+
+/// Layer rejecting requests whose token role or team role lacks `permission`,
+/// whose API key lacks the scope, whose unverified account may not use it yet,
+/// or whose impersonation token may not use it.
+///
+/// ```ignore
+/// .route("/api/clients/:id", delete(delete_client.layer(require(Permission::ManageClients))))
+/// ```
+///
+/// Must run inside `auth_middleware`, which puts the token claims on the request.
+pub fn require(permission: Permission) -> RequirePermission {
+    RequirePermission { permission }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RequirePermission {
+    permission: Permission,
+}
+
+impl<S> Layer<S> for RequirePermission {
+    type Service = RequirePermissionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequirePermissionService {
+            inner,
+            permission: self.permission,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequirePermissionService<S> {
+    inner: S,
+    permission: Permission,
+}
+
+impl<S> Service<Request> for RequirePermissionService<S>
+where
+    S: Service<Request, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let claims = request.extensions().get::<Claims>();
+        let role = claims.map(|claims| Role::parse(&claims.role));
+        let team_role = claims.and_then(TeamRole::from_claims);
+
+        let unverified = request.extensions().get::<UnverifiedEmail>().is_some();
+        let impersonated = claims.is_some_and(|claims| claims.impersonated_by.is_some());
+        let out_of_scope = request
+            .extensions()
+            .get::<ApiKeyScopes>()
+            .is_some_and(|scopes| !scopes.0.contains(&self.permission));
+
+        let rejection = match role {
+            None | Some(None) => Some(AppError::Unauthorized),
+            Some(Some(role)) if !role.has_permission(self.permission) => Some(AppError::Forbidden),
+            Some(Some(_)) if team_role.is_some_and(|team_role| !team_role.has_permission(self.permission)) => {
+                Some(AppError::Forbidden)
+            }
+            Some(Some(_)) if out_of_scope => Some(AppError::Forbidden),
+            Some(Some(_)) if unverified && self.permission.requires_verified_email() => Some(AppError::EmailNotVerified),
+            Some(Some(_)) if impersonated && !self.permission.allowed_while_impersonating() => {
+                Some(AppError::NotWhileImpersonating)
+            }
+            Some(Some(_)) => None,
+        };
+
+        if let Some(error) = rejection {
+            return Box::pin(async move { Ok(error.into_response()) });
+        }
+
+        // Use the instance that was driven to readiness and leave a fresh clone behind
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(async move { inner.call(request).await })
+    }
+}
+
+impl CurrentUser {
+    pub fn is_admin(&self) -> bool {
+        self.role == Role::Admin
+    }
+
+    /// For routes that change the caller's own credentials or sessions, which an
+    /// admin impersonating the user must not touch.
+    pub fn ensure_not_impersonating(&self) -> Result<(), AppError> {
+        match self.impersonated_by {
+            Some(_) => Err(AppError::NotWhileImpersonating),
+            None => Ok(()),
+        }
+    }
+
+    /// The client profile of a client user.
+    pub fn linked_client_id(&self) -> Result<Uuid, AppError> {
+        match (self.role, self.client_id) {
+            (Role::Client, Some(client_id)) => Ok(client_id),
+            _ => Err(AppError::Forbidden),
+        }
+    }
+
+    /// The worker profile of a worker user.
+    pub fn linked_worker_id(&self) -> Result<Uuid, AppError> {
+        match (self.role, self.worker_id) {
+            (Role::Worker, Some(worker_id)) => Ok(worker_id),
+            _ => Err(AppError::Forbidden),
+        }
+    }
+
+    /// Client filter for list endpoints: clients always see only their own
+    /// records, everyone else gets the filter they asked for.
+    pub fn client_filter(&self, requested: Option<Uuid>) -> Result<Option<Uuid>, AppError> {
+        match self.role {
+            Role::Client => self.linked_client_id().map(Some),
+            _ => Ok(requested),
+        }
+    }
+
+    /// Worker filter for list endpoints: workers always see only their own records.
+    pub fn worker_filter(&self, requested: Option<Uuid>) -> Result<Option<Uuid>, AppError> {
+        match self.role {
+            Role::Worker => self.linked_worker_id().map(Some),
+            _ => Ok(requested),
+        }
+    }
+
+    /// Admins and the client itself may access records owned by `client_id`.
+    pub fn authorize_client(&self, client_id: Uuid) -> Result<(), AppError> {
+        if self.is_admin() || self.linked_client_id().ok() == Some(client_id) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+
+    /// Hiring managers work only on jobs assigned to them; returns the user to
+    /// filter assignments by, or `None` when the caller sees every job of its client.
+    pub fn assignment_filter(&self) -> Option<Uuid> {
+        match self.team_role {
+            Some(TeamRole::HiringManager) => Some(self.id),
+            _ => None,
+        }
+    }
+
+    /// Admins and the worker itself may access records owned by `worker_id`.
+    pub fn authorize_worker(&self, worker_id: Uuid) -> Result<(), AppError> {
+        if self.is_admin() || self.linked_worker_id().ok() == Some(worker_id) {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+}
+
+/// Locks the client's active owners and fails if `user_id` is the only one. Call
+/// before anything that takes an owner out of their organization.
+pub async fn ensure_other_owner(conn: &mut PgConnection, client_id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+    let owners = sqlx::query_scalar!(
+        "SELECT id FROM users WHERE client_id = $1 AND team_role = 'owner' AND is_active = true FOR UPDATE",
+        client_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    if owners == [user_id] {
+        return Err(AppError::Conflict("An organization needs at least one owner".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Hiring managers must be assigned to the job; anyone else passes.
+pub async fn authorize_assigned_job(pool: &PgPool, user: &CurrentUser, job_id: Uuid) -> Result<(), AppError> {
+    let Some(user_id) = user.assignment_filter() else {
+        return Ok(());
+    };
+
+    let assigned = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM job_assignments WHERE job_id = $1 AND user_id = $2",
+        job_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if assigned.unwrap_or(0) == 0 {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(())
+}
+
+/// Admins, and the client that owns the job (for hiring managers, if assigned).
+pub async fn authorize_job(pool: &PgPool, user: &CurrentUser, job_id: Uuid) -> Result<(), AppError> {
+    let client_id = sqlx::query_scalar!(
+        "SELECT client_id FROM job_postings WHERE id = $1",
+        job_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    user.authorize_client(client_id)?;
+    authorize_assigned_job(pool, user, job_id).await
+}
+
+/// Admins, the client that owns the job (for hiring managers, if assigned) and
+/// the worker who applied.
+pub async fn authorize_application(pool: &PgPool, user: &CurrentUser, application_id: Uuid) -> Result<(), AppError> {
+    let owners = sqlx::query!(
+        r#"
+        SELECT a.job_id, j.client_id, a.worker_id
+        FROM applications a
+        JOIN job_postings j ON a.job_id = j.id
+        WHERE a.id = $1
+        "#,
+        application_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if user.authorize_client(owners.client_id).is_ok() {
+        return authorize_assigned_job(pool, user, owners.job_id).await;
+    }
+
+    user.authorize_worker(owners.worker_id)
+}
+
+/// Same owners as the offer's application. Drafts don't exist for the candidate
+/// until they are sent.
+pub async fn authorize_offer(pool: &PgPool, user: &CurrentUser, offer_id: Uuid) -> Result<(), AppError> {
+    let offer = sqlx::query!(
+        "SELECT application_id, status FROM offers WHERE id = $1",
+        offer_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if user.role == Role::Worker && offer.status == "draft" {
+        return Err(AppError::NotFound);
+    }
+
+    authorize_application(pool, user, offer.application_id).await
+}
+
+/// Admins, the worker, and clients the worker has applied to (for hiring
+/// managers, to one of their jobs).
+pub async fn authorize_worker_profile(pool: &PgPool, user: &CurrentUser, worker_id: Uuid) -> Result<(), AppError> {
+    if user.authorize_worker(worker_id).is_ok() {
+        return Ok(());
+    }
+
+    let client_id = user.linked_client_id()?;
+    let applied = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM applications a
+        JOIN job_postings j ON a.job_id = j.id
+        WHERE a.worker_id = $1 AND j.client_id = $2
+          AND ($3::uuid IS NULL OR EXISTS (
+              SELECT 1 FROM job_assignments ja WHERE ja.job_id = j.id AND ja.user_id = $3
+          ))
+        "#,
+        worker_id,
+        client_id,
+        user.assignment_filter()
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if applied.unwrap_or(0) == 0 {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(())
+}
+
+/// Hiring managers only handle meetings about a job assigned to them.
+pub async fn authorize_meeting_job(pool: &PgPool, user: &CurrentUser, job_id: Option<Uuid>) -> Result<(), AppError> {
+    match job_id {
+        Some(job_id) => authorize_assigned_job(pool, user, job_id).await,
+        None if user.assignment_filter().is_some() => Err(AppError::Forbidden),
+        None => Ok(()),
+    }
+}
+
+/// Admins, the meeting's client and the invited worker. Hiring managers only
+/// see meetings about their assigned jobs.
+pub async fn authorize_meeting(pool: &PgPool, user: &CurrentUser, meeting_id: Uuid) -> Result<(), AppError> {
+    let owners = sqlx::query!(
+        "SELECT client_id, worker_id, job_id FROM meetings WHERE id = $1",
+        meeting_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::NotFound)?;
+
+    if user.authorize_client(owners.client_id).is_ok() {
+        return authorize_meeting_job(pool, user, owners.job_id).await;
+    }
+
+    match owners.worker_id {
+        Some(worker_id) => user.authorize_worker(worker_id),
+        None => Err(AppError::Forbidden),
+    }
+}
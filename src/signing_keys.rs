@@ -0,0 +1,239 @@
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Duration, Utc};
+use data_encoding::BASE64URL_NOPAD;
+use jsonwebtoken::{DecodingKey, EncodingKey};
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::error::AppError;
+
+/// Secret used when `JWT_SECRET` is unset in development.
+pub const DEVELOPMENT_JWT_SECRET: &str = "your-super-secret-jwt-key-here";
+
+// Values shipped in the docs and examples, refused outside development
+const PLACEHOLDER_JWT_SECRETS: &[&str] = &[
+    DEVELOPMENT_JWT_SECRET,
+    "your-super-secret-jwt-key-here-change-this-in-production",
+];
+
+const MIN_JWT_SECRET_LENGTH: usize = 32;
+
+/// A new key is published this long before it signs anything, so every
+/// instance and JWKS consumer knows it by then.
+pub const KEY_PUBLISH_LEAD_MINUTES: i64 = 10;
+
+/// True for secrets that must not protect a production deployment.
+pub fn is_weak_secret(secret: &str) -> bool {
+    PLACEHOLDER_JWT_SECRETS.contains(&secret) || secret.len() < MIN_JWT_SECRET_LENGTH
+}
+
+/// An Ed25519 key for signing access tokens, known to verifiers by `kid`.
+#[derive(Clone)]
+pub struct SigningKey {
+    pub kid: String,
+    pub activates_at: DateTime<Utc>,
+    encoding: EncodingKey,
+    public_key: Vec<u8>,
+}
+
+impl SigningKey {
+    /// A fresh key and its PKCS#8 document for storage.
+    pub fn generate(activates_at: DateTime<Utc>) -> Result<(Self, Vec<u8>), AppError> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new())
+            .map_err(|_| AppError::InternalServerError("Failed to generate signing key".to_string()))?;
+
+        let mut kid = [0u8; 8];
+        rand::thread_rng().fill_bytes(&mut kid);
+
+        let key = Self::from_pkcs8(hex::encode(kid), pkcs8.as_ref(), activates_at)?;
+        Ok((key, pkcs8.as_ref().to_vec()))
+    }
+
+    pub fn from_pkcs8(kid: String, pkcs8: &[u8], activates_at: DateTime<Utc>) -> Result<Self, AppError> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8)
+            .map_err(|_| AppError::InternalServerError(format!("Signing key {} is corrupt", kid)))?;
+
+        Ok(Self {
+            kid,
+            activates_at,
+            encoding: EncodingKey::from_ed_der(pkcs8),
+            public_key: key_pair.public_key().as_ref().to_vec(),
+        })
+    }
+
+    fn jwk(&self) -> Value {
+        json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "use": "sig",
+            "alg": "EdDSA",
+            "kid": self.kid,
+            "x": BASE64URL_NOPAD.encode(&self.public_key)
+        })
+    }
+}
+
+/// The keys this instance signs and verifies with, shared by every clone.
+#[derive(Clone, Default)]
+pub struct KeyRing {
+    keys: Arc<RwLock<Vec<SigningKey>>>,
+}
+
+impl KeyRing {
+    pub fn new(keys: Vec<SigningKey>) -> Self {
+        let ring = Self::default();
+        ring.replace(keys);
+        ring
+    }
+
+    fn replace(&self, mut keys: Vec<SigningKey>) {
+        keys.sort_by_key(|key| key.activates_at);
+        *self.keys.write().unwrap() = keys;
+    }
+
+    /// The newest key that has been published long enough to sign with.
+    pub fn signing_key(&self, now: DateTime<Utc>) -> Option<(String, EncodingKey)> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|key| key.activates_at <= now)
+            .map(|key| (key.kid.clone(), key.encoding.clone()))
+    }
+
+    /// Any known key, including ones not yet signing and ones rotated out
+    /// whose tokens may still be live.
+    pub fn decoding_key(&self, kid: &str) -> Option<DecodingKey> {
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .find(|key| key.kid == kid)
+            .map(|key| DecodingKey::from_ed_der(&key.public_key))
+    }
+
+    /// Public keys as a JSON Web Key Set.
+    pub fn jwks(&self) -> Value {
+        let keys: Vec<Value> = self.keys.read().unwrap().iter().map(SigningKey::jwk).collect();
+        json!({ "keys": keys })
+    }
+}
+
+// Private keys are stored encrypted with a key derived from JWT_SECRET; the
+// kid is bound in as associated data so rows cannot be swapped
+fn cipher(secret: &str) -> LessSafeKey {
+    let key = UnboundKey::new(&AES_256_GCM, &Sha256::digest(secret.as_bytes()))
+        .expect("SHA-256 output is a valid AES-256 key");
+    LessSafeKey::new(key)
+}
+
+/// Encrypts a private key for storage: nonce followed by ciphertext and tag.
+pub fn seal_private_key(secret: &str, kid: &str, pkcs8: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut sealed = pkcs8.to_vec();
+    cipher(secret)
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(kid.as_bytes()), &mut sealed)
+        .expect("Private keys are far below the AES-GCM size limit");
+
+    [nonce.as_slice(), &sealed].concat()
+}
+
+/// The private key, or `None` if it was sealed under another secret or tampered with.
+pub fn open_private_key(secret: &str, kid: &str, sealed: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut buffer = ciphertext.to_vec();
+    let pkcs8 = cipher(secret)
+        .open_in_place(nonce, Aad::from(kid.as_bytes()), &mut buffer)
+        .ok()?;
+
+    Some(pkcs8.to_vec())
+}
+
+/// Brings the stored keys up to date and loads them into `keyring`:
+/// drops keys superseded more than `retention` ago, and adds a key when the
+/// newest one is older than `rotation` (or when there is none to sign with).
+/// Safe to run from several instances at once.
+pub async fn sync_signing_keys(
+    pool: &PgPool,
+    keyring: &KeyRing,
+    secret: &str,
+    rotation: Duration,
+    retention: Duration,
+) -> Result<(), AppError> {
+    let now = Utc::now();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!("SELECT pg_advisory_xact_lock(hashtext('jwt_signing_keys'))")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM jwt_signing_keys k
+        WHERE EXISTS (
+            SELECT 1 FROM jwt_signing_keys newer
+            WHERE newer.activates_at > k.activates_at AND newer.activates_at < $1
+        )
+        "#,
+        now - retention
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let rows = sqlx::query!("SELECT kid, private_key, activates_at, created_at FROM jwt_signing_keys")
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let newest_created_at = rows.iter().map(|row| row.created_at).max();
+    let mut keys = Vec::new();
+    for row in rows {
+        let Some(pkcs8) = open_private_key(secret, &row.kid, &row.private_key) else {
+            tracing::warn!("Signing key {} cannot be decrypted with the current JWT_SECRET", row.kid);
+            continue;
+        };
+        keys.push(SigningKey::from_pkcs8(row.kid, &pkcs8, row.activates_at)?);
+    }
+
+    let can_sign = keys.iter().any(|key| key.activates_at <= now);
+    let activates_at = if !can_sign {
+        Some(now)
+    } else if newest_created_at.is_some_and(|created_at| created_at < now - rotation) {
+        Some(now + Duration::minutes(KEY_PUBLISH_LEAD_MINUTES))
+    } else {
+        None
+    };
+
+    if let Some(activates_at) = activates_at {
+        let (key, pkcs8) = SigningKey::generate(activates_at)?;
+
+        sqlx::query!(
+            "INSERT INTO jwt_signing_keys (kid, private_key, activates_at) VALUES ($1, $2, $3)",
+            key.kid,
+            seal_private_key(secret, &key.kid, &pkcs8),
+            key.activates_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tracing::info!("Created signing key {}, signing from {}", key.kid, key.activates_at);
+        keys.push(key);
+    }
+
+    tx.commit().await?;
+
+    keyring.replace(keys);
+    Ok(())
+}
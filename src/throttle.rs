@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How failed logins against one key (an account or an IP) are slowed down.
+#[derive(Debug, Clone, Copy)]
+pub struct ThrottlePolicy {
+    /// Failures allowed before any delay applies
+    pub free_attempts: u32,
+    /// Delay after the first failure past `free_attempts`; doubles with every further failure
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Failures after which the key is locked out for `lockout_duration`
+    pub lockout_after: u32,
+    pub lockout_duration: Duration,
+    /// Failures are forgotten once none happened for this long
+    pub reset_after: Duration,
+}
+
+impl ThrottlePolicy {
+    pub fn per_account() -> Self {
+        Self {
+            free_attempts: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            lockout_after: 10,
+            lockout_duration: Duration::from_secs(15 * 60),
+            reset_after: Duration::from_secs(15 * 60),
+        }
+    }
+
+    // Looser, since many users can share an address
+    pub fn per_ip() -> Self {
+        Self {
+            free_attempts: 10,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            lockout_after: 50,
+            lockout_duration: Duration::from_secs(15 * 60),
+            reset_after: Duration::from_secs(15 * 60),
+        }
+    }
+
+    fn delay_after(&self, failures: u32) -> Option<Duration> {
+        if failures >= self.lockout_after {
+            return Some(self.lockout_duration);
+        }
+
+        if failures <= self.free_attempts {
+            return None;
+        }
+
+        let doublings = (failures - self.free_attempts - 1).min(16);
+        Some((self.base_delay * 2u32.pow(doublings)).min(self.max_delay))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Attempts {
+    failures: u32,
+    last_failure: Instant,
+    blocked_until: Option<Instant>,
+}
+
+// Entries kept before stale ones are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+#[derive(Debug)]
+struct Tracker<K> {
+    policy: ThrottlePolicy,
+    attempts: HashMap<K, Attempts>,
+}
+
+impl<K: Eq + Hash> Tracker<K> {
+    fn new(policy: ThrottlePolicy) -> Self {
+        Self { policy, attempts: HashMap::new() }
+    }
+
+    fn retry_after(&self, key: &K, now: Instant) -> Option<Duration> {
+        self.attempts
+            .get(key)
+            .and_then(|attempts| attempts.blocked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    fn record_failure(&mut self, key: K, now: Instant) {
+        let reset_after = self.policy.reset_after;
+        if self.attempts.len() >= PRUNE_THRESHOLD {
+            self.attempts.retain(|_, attempts| now.duration_since(attempts.last_failure) < reset_after);
+        }
+
+        let attempts = self.attempts.entry(key).or_insert(Attempts {
+            failures: 0,
+            last_failure: now,
+            blocked_until: None,
+        });
+
+        let still_blocked = attempts.blocked_until.is_some_and(|until| until > now);
+        if !still_blocked && now.duration_since(attempts.last_failure) >= reset_after {
+            attempts.failures = 0;
+        }
+
+        attempts.failures += 1;
+        attempts.last_failure = now;
+        if let Some(delay) = self.policy.delay_after(attempts.failures) {
+            attempts.blocked_until = Some(now + delay);
+        }
+    }
+
+    fn clear(&mut self, key: &K) {
+        self.attempts.remove(key);
+    }
+}
+
+/// In-memory failed-login tracking per account and per client IP.
+///
+/// Every caller passes the current `Instant`, which keeps the backoff logic
+/// deterministic in tests. State is per process and is lost on restart.
+#[derive(Debug, Clone)]
+pub struct LoginThrottle {
+    accounts: Arc<Mutex<Tracker<String>>>,
+    ips: Arc<Mutex<Tracker<IpAddr>>>,
+}
+
+fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+impl LoginThrottle {
+    pub fn new(account_policy: ThrottlePolicy, ip_policy: ThrottlePolicy) -> Self {
+        Self {
+            accounts: Arc::new(Mutex::new(Tracker::new(account_policy))),
+            ips: Arc::new(Mutex::new(Tracker::new(ip_policy))),
+        }
+    }
+
+    /// `Err` with the time left when either the account or the IP is blocked.
+    pub fn check(&self, email: &str, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let account_wait = self.accounts.lock().unwrap().retry_after(&account_key(email), now);
+        let ip_wait = self.ips.lock().unwrap().retry_after(&ip, now);
+
+        match account_wait.max(ip_wait) {
+            Some(wait) => Err(wait),
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_failure(&self, email: &str, ip: IpAddr, now: Instant) {
+        self.accounts.lock().unwrap().record_failure(account_key(email), now);
+        self.ips.lock().unwrap().record_failure(ip, now);
+    }
+
+    /// Forgets the account's failures. The IP keeps its count so that one valid
+    /// account cannot be used to reset the limit for guesses against others.
+    pub fn record_success(&self, email: &str) {
+        self.accounts.lock().unwrap().clear(&account_key(email));
+    }
+
+    pub fn unlock_account(&self, email: &str) {
+        self.accounts.lock().unwrap().clear(&account_key(email));
+    }
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        Self::new(ThrottlePolicy::per_account(), ThrottlePolicy::per_ip())
+    }
+}
@@ -0,0 +1,102 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+/// RFC 6238 parameters understood by every authenticator app: SHA-1, 6 digits, 30 seconds.
+pub const STEP_SECONDS: i64 = 30;
+pub const DIGITS: u32 = 6;
+
+// Steps accepted on either side of the current one, for clock drift
+const SKEW_STEPS: i64 = 1;
+
+/// A new random 160-bit secret, base32 encoded as authenticator apps expect.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+fn decode_secret(secret: &str) -> Option<Vec<u8>> {
+    let normalized: String = secret
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '=')
+        .collect::<String>()
+        .to_uppercase();
+
+    BASE32_NOPAD.decode(normalized.as_bytes()).ok()
+}
+
+// RFC 4226 HOTP with dynamic truncation
+fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([digest[offset], digest[offset + 1], digest[offset + 2], digest[offset + 3]])
+        & 0x7fff_ffff;
+
+    binary % 10u32.pow(DIGITS)
+}
+
+/// The time step containing `unix_time`.
+pub fn step_at(unix_time: i64) -> i64 {
+    unix_time.div_euclid(STEP_SECONDS)
+}
+
+/// The code an authenticator shows at `unix_time`, or `None` for a malformed secret.
+pub fn code_at(secret: &str, unix_time: i64) -> Option<String> {
+    let key = decode_secret(secret)?;
+    Some(format!("{:0width$}", hotp(&key, step_at(unix_time) as u64), width = DIGITS as usize))
+}
+
+/// Checks `code` against the steps around `unix_time` and returns the step it
+/// matched. Steps up to `last_used_step` are refused so a code works only once.
+pub fn verify(secret: &str, code: &str, unix_time: i64, last_used_step: Option<i64>) -> Option<i64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let key = decode_secret(secret)?;
+    let current = step_at(unix_time);
+
+    (current - SKEW_STEPS..=current + SKEW_STEPS)
+        .filter(|step| *step >= 0)
+        .filter(|step| match last_used_step {
+            Some(last) => *step > last,
+            None => true,
+        })
+        .find(|step| {
+            let expected = format!("{:0width$}", hotp(&key, *step as u64), width = DIGITS as usize);
+            constant_time_eq(expected.as_bytes(), code.as_bytes())
+        })
+}
+
+/// `otpauth://` URI for enrollment, usually shown as a QR code.
+pub fn provisioning_uri(secret: &str, account: &str, issuer: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'@' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
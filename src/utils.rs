@@ -110,6 +110,37 @@ pub fn validate_phone(phone: &str) -> bool {
     phone.chars().all(|c| c.is_ascii_digit() || " +-()".contains(c))
 }
 
+/// Escapes `%`, `_` and `\` so user input matches literally inside a LIKE pattern.
+pub fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Highest salary accepted on offers and placements, in whole currency units. Far above
+/// any real salary, and low enough that fee arithmetic cannot overflow.
+pub const MAX_SALARY: i64 = 1_000_000_000;
+
+pub fn is_valid_salary(salary: i64) -> bool {
+    (1..=MAX_SALARY).contains(&salary)
+}
+
+/// Upper-cases an ISO 4217 code, defaulting to USD. Returns None if it is not three letters.
+pub fn normalize_currency(currency: Option<&str>) -> Option<String> {
+    let currency = currency.unwrap_or("USD").trim().to_uppercase();
+    
+    if currency.len() == 3 && currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(currency)
+    } else {
+        None
+    }
+}
+
 pub fn calculate_skill_match_score(job_skills: &[String], worker_skills: &[String]) -> f64 {
     if job_skills.is_empty() {
         return 0.0;
@@ -131,3 +162,10 @@ pub fn calculate_experience_score(years_experience: i32, required_years: i32) ->
         (years_experience as f64 / required_years.max(1) as f64) * 100.0
     }
 }
+
+/// Public URL of the app, used to build links in emails
+pub fn app_base_url() -> String {
+    std::env::var("APP_BASE_URL")
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| "http://localhost:3000".to_string())
+}
@@ -0,0 +1,348 @@
+use ciborium::value::Value;
+use data_encoding::BASE64URL_NOPAD;
+use rand::RngCore;
+use reqwest::Url;
+use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, ED25519};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// COSE algorithm identifiers of the credentials we accept.
+pub const COSE_ALG_ES256: i64 = -7;
+pub const COSE_ALG_EDDSA: i64 = -8;
+
+/// How long the browser has to complete a ceremony.
+pub const CHALLENGE_TTL_MINUTES: i64 = 5;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+// rpIdHash (32) + flags (1) + signCount (4)
+const AUTHENTICATOR_DATA_MIN_LEN: usize = 37;
+
+/// Who we are to authenticators. Credentials are bound to `id`, a domain, and
+/// ceremonies are only accepted from `origin`.
+#[derive(Debug, Clone)]
+pub struct RelyingParty {
+    pub id: String,
+    pub origin: String,
+    pub name: String,
+}
+
+impl RelyingParty {
+    /// The relying party of a site, identified by its host.
+    pub fn from_origin(origin: &str, name: &str) -> Option<Self> {
+        let origin = origin.trim_end_matches('/');
+        let host = Url::parse(origin).ok()?.host_str()?.to_string();
+
+        Some(Self {
+            id: host,
+            origin: origin.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// A new credential as sent by the browser (`PublicKeyCredential.toJSON()`).
+#[derive(Debug, Deserialize)]
+pub struct RegistrationCredential {
+    pub id: String,
+    pub response: AttestationResponse,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
+
+/// A signed login assertion as sent by the browser.
+#[derive(Debug, Deserialize)]
+pub struct AssertionCredential {
+    pub id: String,
+    pub response: AssertionResponse,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    ceremony: String,
+    challenge: String,
+    origin: String,
+    #[serde(rename = "crossOrigin", default)]
+    cross_origin: bool,
+}
+
+/// A verified new credential, to be stored with its user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedCredential {
+    /// base64url, as browsers send it back at login
+    pub credential_id: String,
+    pub algorithm: i64,
+    /// Uncompressed P-256 point for ES256, raw 32-byte key for EdDSA
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::BadRequest(format!("Invalid passkey response: {}", message))
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, AppError> {
+    BASE64URL_NOPAD
+        .decode(value.trim_end_matches('=').as_bytes())
+        .map_err(|_| invalid("not base64url"))
+}
+
+/// Random single-use challenge for a ceremony.
+pub fn new_challenge() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE64URL_NOPAD.encode(&bytes)
+}
+
+/// Options for `navigator.credentials.create()`, in the WebAuthn JSON format.
+/// The user handle is the user id, so the authenticator can find the account
+/// at login without an email address.
+pub fn creation_options(
+    rp: &RelyingParty,
+    user_id: Uuid,
+    email: &str,
+    challenge: &str,
+    existing_credentials: &[String],
+) -> serde_json::Value {
+    json!({
+        "rp": { "id": rp.id, "name": rp.name },
+        "user": {
+            "id": BASE64URL_NOPAD.encode(user_id.as_bytes()),
+            "name": email,
+            "displayName": email
+        },
+        "challenge": challenge,
+        "pubKeyCredParams": [
+            { "type": "public-key", "alg": COSE_ALG_ES256 },
+            { "type": "public-key", "alg": COSE_ALG_EDDSA }
+        ],
+        "timeout": CHALLENGE_TTL_MINUTES * 60 * 1000,
+        "excludeCredentials": existing_credentials
+            .iter()
+            .map(|id| json!({ "type": "public-key", "id": id }))
+            .collect::<Vec<_>>(),
+        "authenticatorSelection": {
+            "residentKey": "required",
+            "userVerification": "required"
+        },
+        "attestation": "none"
+    })
+}
+
+/// Options for `navigator.credentials.get()`. No credentials are listed: the
+/// authenticator offers the passkeys it holds for this site.
+pub fn request_options(rp: &RelyingParty, challenge: &str) -> serde_json::Value {
+    json!({
+        "rpId": rp.id,
+        "challenge": challenge,
+        "timeout": CHALLENGE_TTL_MINUTES * 60 * 1000,
+        "userVerification": "required"
+    })
+}
+
+/// The challenge a response answers, to look up the pending ceremony.
+pub fn client_data_challenge(client_data_json: &str) -> Result<String, AppError> {
+    let client_data: ClientData = serde_json::from_slice(&decode(client_data_json)?)
+        .map_err(|_| invalid("malformed client data"))?;
+
+    Ok(client_data.challenge)
+}
+
+fn check_client_data(rp: &RelyingParty, raw: &[u8], ceremony: &str, challenge: &str) -> Result<(), AppError> {
+    let client_data: ClientData = serde_json::from_slice(raw).map_err(|_| invalid("malformed client data"))?;
+
+    if client_data.ceremony != ceremony {
+        return Err(invalid("wrong ceremony"));
+    }
+
+    if client_data.challenge != challenge {
+        return Err(invalid("challenge mismatch"));
+    }
+
+    // A phishing site gets a response for its own origin, which fails here
+    if client_data.origin != rp.origin || client_data.cross_origin {
+        return Err(invalid("unexpected origin"));
+    }
+
+    Ok(())
+}
+
+/// Checks the parts of authenticator data both ceremonies share and returns
+/// the flags and sign counter.
+fn check_authenticator_data(rp: &RelyingParty, data: &[u8]) -> Result<(u8, u32), AppError> {
+    if data.len() < AUTHENTICATOR_DATA_MIN_LEN {
+        return Err(invalid("authenticator data too short"));
+    }
+
+    if data[..32] != Sha256::digest(rp.id.as_bytes())[..] {
+        return Err(invalid("credential belongs to another site"));
+    }
+
+    let flags = data[32];
+    if flags & FLAG_USER_PRESENT == 0 || flags & FLAG_USER_VERIFIED == 0 {
+        return Err(invalid("user was not verified by the authenticator"));
+    }
+
+    let sign_count = u32::from_be_bytes([data[33], data[34], data[35], data[36]]);
+
+    Ok((flags, sign_count))
+}
+
+fn cose_int(entries: &[(Value, Value)], label: i128) -> Option<i128> {
+    entries.iter().find_map(|(key, value)| match (key, value) {
+        (Value::Integer(key), Value::Integer(value)) if i128::from(*key) == label => Some(i128::from(*value)),
+        _ => None,
+    })
+}
+
+fn cose_bytes(entries: &[(Value, Value)], label: i128) -> Option<&[u8]> {
+    entries.iter().find_map(|(key, value)| match (key, value) {
+        (Value::Integer(key), Value::Bytes(value)) if i128::from(*key) == label => Some(value.as_slice()),
+        _ => None,
+    })
+}
+
+/// Reads a COSE_Key (RFC 9053) into its algorithm and the key bytes `ring` verifies with.
+fn parse_cose_key(bytes: &[u8]) -> Result<(i64, Vec<u8>), AppError> {
+    let Ok(Value::Map(entries)) = ciborium::de::from_reader::<Value, _>(bytes) else {
+        return Err(invalid("malformed credential public key"));
+    };
+
+    // kty 2 is EC2 (crv 1: P-256), kty 1 is OKP (crv 6: Ed25519)
+    match (cose_int(&entries, 1), cose_int(&entries, 3), cose_int(&entries, -1)) {
+        (Some(2), Some(-7), Some(1)) => {
+            let x = cose_bytes(&entries, -2).filter(|x| x.len() == 32);
+            let y = cose_bytes(&entries, -3).filter(|y| y.len() == 32);
+            let (Some(x), Some(y)) = (x, y) else {
+                return Err(invalid("malformed P-256 key"));
+            };
+
+            let mut point = vec![0x04];
+            point.extend_from_slice(x);
+            point.extend_from_slice(y);
+            Ok((COSE_ALG_ES256, point))
+        }
+        (Some(1), Some(-8), Some(6)) => {
+            let x = cose_bytes(&entries, -2)
+                .filter(|x| x.len() == 32)
+                .ok_or_else(|| invalid("malformed Ed25519 key"))?;
+            Ok((COSE_ALG_EDDSA, x.to_vec()))
+        }
+        _ => Err(invalid("unsupported credential algorithm, use ES256 or EdDSA")),
+    }
+}
+
+/// Verifies the response to `navigator.credentials.create()`. Attestation is
+/// not requested, so any authenticator that verifies its user is accepted.
+pub fn verify_registration(
+    rp: &RelyingParty,
+    challenge: &str,
+    credential: &RegistrationCredential,
+) -> Result<VerifiedCredential, AppError> {
+    check_client_data(rp, &decode(&credential.response.client_data_json)?, "webauthn.create", challenge)?;
+
+    let attestation = decode(&credential.response.attestation_object)?;
+    let Ok(Value::Map(attestation)) = ciborium::de::from_reader::<Value, _>(attestation.as_slice()) else {
+        return Err(invalid("malformed attestation object"));
+    };
+
+    let auth_data = attestation
+        .iter()
+        .find_map(|(key, value)| match (key, value) {
+            (Value::Text(key), Value::Bytes(value)) if key == "authData" => Some(value.as_slice()),
+            _ => None,
+        })
+        .ok_or_else(|| invalid("missing authenticator data"))?;
+
+    let (flags, sign_count) = check_authenticator_data(rp, auth_data)?;
+    if flags & FLAG_ATTESTED_CREDENTIAL == 0 {
+        return Err(invalid("missing credential data"));
+    }
+
+    // AAGUID (16), credential id length (2), credential id, COSE public key
+    let attested = &auth_data[AUTHENTICATOR_DATA_MIN_LEN..];
+    if attested.len() < 18 {
+        return Err(invalid("credential data too short"));
+    }
+
+    let id_len = u16::from_be_bytes([attested[16], attested[17]]) as usize;
+    let credential_id = attested
+        .get(18..18 + id_len)
+        .ok_or_else(|| invalid("credential data too short"))?;
+
+    if BASE64URL_NOPAD.encode(credential_id) != credential.id.trim_end_matches('=') {
+        return Err(invalid("credential id mismatch"));
+    }
+
+    let (algorithm, public_key) = parse_cose_key(&attested[18 + id_len..])?;
+
+    Ok(VerifiedCredential {
+        credential_id: BASE64URL_NOPAD.encode(credential_id),
+        algorithm,
+        public_key,
+        sign_count,
+    })
+}
+
+/// Verifies a login assertion against the stored credential and returns the
+/// new sign counter. A counter that doesn't increase means the credential may
+/// have been cloned, so the login is refused.
+pub fn verify_assertion(
+    rp: &RelyingParty,
+    challenge: &str,
+    credential: &AssertionCredential,
+    algorithm: i64,
+    public_key: &[u8],
+    stored_sign_count: u32,
+) -> Result<u32, AppError> {
+    let client_data_json = decode(&credential.response.client_data_json)?;
+    check_client_data(rp, &client_data_json, "webauthn.get", challenge)?;
+
+    let auth_data = decode(&credential.response.authenticator_data)?;
+    let (_, sign_count) = check_authenticator_data(rp, &auth_data)?;
+
+    let mut signed = auth_data;
+    signed.extend_from_slice(&Sha256::digest(&client_data_json));
+    let signature = decode(&credential.response.signature)?;
+
+    let verified = match algorithm {
+        COSE_ALG_ES256 => UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, public_key).verify(&signed, &signature),
+        COSE_ALG_EDDSA => UnparsedPublicKey::new(&ED25519, public_key).verify(&signed, &signature),
+        _ => return Err(AppError::InternalServerError(format!("Stored passkey has unknown algorithm {}", algorithm))),
+    };
+
+    if verified.is_err() {
+        return Err(AppError::Unauthorized);
+    }
+
+    // Authenticators without a counter always send 0
+    if (sign_count != 0 || stored_sign_count != 0) && sign_count <= stored_sign_count {
+        tracing::warn!("Passkey {} sign counter did not increase, possible clone", credential.id);
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(sign_count)
+}
@@ -57,8 +57,9 @@ psql -U postgres -lqt | cut -d \| -f 1 | findstr /C:"recruitment" >nul
 if %errorlevel% neq 0 (
     echo 📊 Creating database...
     createdb -U postgres recruitment
-    echo 📊 Loading schema...
-    psql -U postgres -d recruitment -f database.sql
+    echo 📊 Loading schema and demo data...
+    cargo run -- migrate up
+    cargo run -- seed
     echo ✅ Database setup complete
 ) else (
     echo ✅ Database already exists
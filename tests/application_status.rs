@@ -0,0 +1,56 @@
+use recruitment_backend::models::ApplicationStatus;
+
+#[test]
+fn test_application_status_happy_path() {
+    let path = [
+        ApplicationStatus::Pending,
+        ApplicationStatus::Reviewing,
+        ApplicationStatus::Interview,
+        ApplicationStatus::Offer,
+        ApplicationStatus::Hired,
+    ];
+
+    for step in path.windows(2) {
+        assert!(step[0].can_transition_to(step[1]), "{} -> {} should be allowed", step[0], step[1]);
+    }
+}
+
+#[test]
+fn test_application_status_rejects_skipping_stages() {
+    assert!(!ApplicationStatus::Pending.can_transition_to(ApplicationStatus::Hired));
+    assert!(!ApplicationStatus::Reviewing.can_transition_to(ApplicationStatus::Offer));
+    assert!(!ApplicationStatus::Interview.can_transition_to(ApplicationStatus::Reviewing));
+}
+
+#[test]
+fn test_application_status_terminal_states() {
+    for status in [ApplicationStatus::Hired, ApplicationStatus::Rejected, ApplicationStatus::Withdrawn] {
+        assert!(status.is_terminal());
+        assert!(!status.can_transition_to(ApplicationStatus::Pending));
+    }
+
+    for status in [ApplicationStatus::Pending, ApplicationStatus::Reviewing, ApplicationStatus::Interview, ApplicationStatus::Offer] {
+        assert!(status.can_transition_to(ApplicationStatus::Rejected));
+        assert!(status.can_transition_to(ApplicationStatus::Withdrawn));
+    }
+}
+
+#[test]
+fn test_application_status_path_walks_forward() {
+    use ApplicationStatus::*;
+
+    assert_eq!(Pending.path_to(Interview), Some(vec![Reviewing, Interview]));
+    assert_eq!(Interview.path_to(Hired), Some(vec![Offer, Hired]));
+    assert_eq!(Offer.path_to(Offer), Some(vec![]));
+    assert_eq!(Reviewing.path_to(Rejected), Some(vec![Rejected]));
+}
+
+#[test]
+fn test_application_status_path_never_goes_back() {
+    use ApplicationStatus::*;
+
+    assert_eq!(Interview.path_to(Reviewing), None);
+    assert_eq!(Offer.path_to(Pending), None);
+    assert_eq!(Rejected.path_to(Hired), None);
+    assert_eq!(Withdrawn.path_to(Rejected), None);
+}
@@ -2,6 +2,9 @@ use reqwest;
 use serde_json::json;
 use std::collections::HashMap;
 
+mod mock_idp;
+mod soft_authenticator;
+
 #[tokio::test]
 async fn test_health_check() {
     let client = reqwest::Client::new();
@@ -20,61 +23,18 @@ async fn test_health_check() {
 #[tokio::test]
 async fn test_user_registration_and_login() {
     let client = reqwest::Client::new();
-    
-    // Register a new user
-    let register_payload = json!({
-        "email": "test@example.com",
-        "password": "password123",
-        "role": "admin"
-    });
-    
-    let register_response = client
-        .post("http://localhost:3000/api/auth/register")
-        .json(&register_payload)
-        .send()
-        .await
-        .expect("Failed to execute request");
-
-    assert_eq!(register_response.status(), 201);
-    
-    // Login with the new user
-    let login_payload = json!({
-        "email": "test@example.com",
-        "password": "password123"
-    });
-    
-    let login_response = client
-        .post("http://localhost:3000/api/auth/login")
-        .json(&login_payload)
-        .send()
-        .await
-        .expect("Failed to execute request");
 
-    assert_eq!(login_response.status(), 200);
-    
-    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
-    assert!(login_body["data"]["token"].is_string());
+    // Registers a worker for a new profile and logs in with it
+    let login = new_worker_login(&client).await;
+    assert!(login["token"].is_string());
 }
 
 #[tokio::test]
 async fn test_create_client() {
     let client = reqwest::Client::new();
     
-    // First login to get token
-    let login_payload = json!({
-        "email": "test@example.com",
-        "password": "password123"
-    });
-    
-    let login_response = client
-        .post("http://localhost:3000/api/auth/login")
-        .json(&login_payload)
-        .send()
-        .await
-        .expect("Failed to execute request");
-
-    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
-    let token = login_body["data"]["token"].as_str().expect("No token found");
+    // Act as the test admin
+    let token = bootstrap_admin_token(&client).await;
     
     // Create a client
     let client_payload = json!({
@@ -101,21 +61,8 @@ async fn test_create_client() {
 async fn test_create_worker() {
     let client = reqwest::Client::new();
     
-    // Login to get token
-    let login_payload = json!({
-        "email": "test@example.com",
-        "password": "password123"
-    });
-    
-    let login_response = client
-        .post("http://localhost:3000/api/auth/login")
-        .json(&login_payload)
-        .send()
-        .await
-        .expect("Failed to execute request");
-
-    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
-    let token = login_body["data"]["token"].as_str().expect("No token found");
+    // Act as the test admin
+    let token = bootstrap_admin_token(&client).await;
     
     // Create a worker
     let worker_payload = json!({
@@ -144,21 +91,8 @@ async fn test_create_worker() {
 async fn test_job_matching() {
     let client = reqwest::Client::new();
     
-    // Login to get token
-    let login_payload = json!({
-        "email": "test@example.com",
-        "password": "password123"
-    });
-    
-    let login_response = client
-        .post("http://localhost:3000/api/auth/login")
-        .json(&login_payload)
-        .send()
-        .await
-        .expect("Failed to execute request");
-
-    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
-    let token = login_body["data"]["token"].as_str().expect("No token found");
+    // Act as the test admin
+    let token = bootstrap_admin_token(&client).await;
     
     // Create a job posting
     let job_payload = json!({
@@ -181,3 +115,1785 @@ async fn test_job_matching() {
     // Note: This test would need a real client ID to work properly
     // In a real test environment, you would first create a client and use its ID
 }
+
+// The admin every test acts as. Create it before running the suite with
+// `ADMIN_PASSWORD=... cargo run -- create-admin EMAIL`; set TEST_ADMIN_EMAIL and
+// TEST_ADMIN_PASSWORD to use another account than these defaults.
+const BOOTSTRAP_ADMIN_EMAIL: &str = "integration-admin@example.com";
+const BOOTSTRAP_ADMIN_PASSWORD: &str = "Integration-Admin-1";
+
+fn bootstrap_admin_credentials() -> (String, String) {
+    (
+        std::env::var("TEST_ADMIN_EMAIL").unwrap_or_else(|_| BOOTSTRAP_ADMIN_EMAIL.to_string()),
+        std::env::var("TEST_ADMIN_PASSWORD").unwrap_or_else(|_| BOOTSTRAP_ADMIN_PASSWORD.to_string()),
+    )
+}
+
+// Logs in as the test admin and returns the login response data
+async fn bootstrap_admin_login(client: &reqwest::Client) -> serde_json::Value {
+    let (email, password) = bootstrap_admin_credentials();
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": email, "password": password }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(
+        login_response.status(),
+        200,
+        "cannot log in as {}; create the admin with `cargo run -- create-admin` first",
+        email
+    );
+
+    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
+    login_body["data"].clone()
+}
+
+async fn bootstrap_admin_token(client: &reqwest::Client) -> String {
+    let login = bootstrap_admin_login(client).await;
+    login["token"].as_str().expect("No token found").to_string()
+}
+
+#[tokio::test]
+async fn test_public_routes_need_no_token() {
+    let client = reqwest::Client::new();
+
+    let health_response = client
+        .get("http://localhost:3000/")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(health_response.status(), 200);
+
+    let jobs_response = client
+        .get("http://localhost:3000/api/public/jobs")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(jobs_response.status(), 200);
+
+    let jobs_body: serde_json::Value = jobs_response.json().await.expect("Failed to parse JSON");
+    assert!(jobs_body["data"]["items"].is_array());
+}
+
+#[tokio::test]
+async fn test_protected_routes_reject_anonymous_requests() {
+    let client = reqwest::Client::new();
+
+    for path in ["/api/auth/me", "/api/clients", "/api/workers", "/api/jobs"] {
+        let response = client
+            .get(format!("http://localhost:3000{}", path))
+            .send()
+            .await
+            .expect("Failed to execute request");
+
+        assert_eq!(response.status(), 401, "{} should require a token", path);
+    }
+}
+
+#[tokio::test]
+async fn test_anonymous_worker_registers_and_logs_in() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+
+    // The agency creates the worker profile
+    let email = format!("worker-{}@example.com", uuid::Uuid::new_v4());
+    let worker_response = client
+        .post("http://localhost:3000/api/workers")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "name": "Flow Worker",
+            "email": email,
+            "skills": ["Rust"],
+            "experience_years": 2
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(worker_response.status(), 201);
+
+    let worker_body: serde_json::Value = worker_response.json().await.expect("Failed to parse JSON");
+    let worker_id = worker_body["data"]["id"].as_str().expect("No worker id").to_string();
+
+    // The candidate signs up without a token, using the profile's email
+    let password = "Flow-Worker-Pass1";
+    let register_response = client
+        .post("http://localhost:3000/api/auth/register")
+        .json(&json!({
+            "email": email,
+            "password": password,
+            "role": "worker",
+            "worker_id": worker_id
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(register_response.status(), 201);
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": email, "password": password }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 200);
+
+    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
+    let token = login_body["data"]["token"].as_str().expect("No token found");
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 200);
+
+    let me_body: serde_json::Value = me_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(me_body["data"]["worker_id"], worker_id.as_str());
+
+    // Workers cannot reach agency-only routes
+    let clients_response = client
+        .get("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(clients_response.status(), 403);
+}
+
+#[tokio::test]
+async fn test_registration_cannot_claim_someone_elses_profile() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+
+    let worker_response = client
+        .post("http://localhost:3000/api/workers")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "name": "Claimed Worker",
+            "email": format!("claimed-{}@example.com", uuid::Uuid::new_v4()),
+            "skills": [],
+            "experience_years": 1
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(worker_response.status(), 201);
+
+    let worker_body: serde_json::Value = worker_response.json().await.expect("Failed to parse JSON");
+
+    let register_response = client
+        .post("http://localhost:3000/api/auth/register")
+        .json(&json!({
+            "email": format!("attacker-{}@example.com", uuid::Uuid::new_v4()),
+            "password": "Attacker-Pass-1",
+            "role": "worker",
+            "worker_id": worker_body["data"]["id"]
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(register_response.status(), 403);
+}
+
+async fn refresh(client: &reqwest::Client, refresh_token: &str) -> reqwest::Response {
+    client
+        .post("http://localhost:3000/api/auth/refresh")
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .expect("Failed to execute request")
+}
+
+#[tokio::test]
+async fn test_refresh_token_rotation_and_reuse_detection() {
+    let client = reqwest::Client::new();
+    let login = bootstrap_admin_login(&client).await;
+    let first = login["refresh_token"].as_str().expect("No refresh token");
+
+    let rotated = refresh(&client, first).await;
+    assert_eq!(rotated.status(), 200);
+
+    let rotated_body: serde_json::Value = rotated.json().await.expect("Failed to parse JSON");
+    let second = rotated_body["data"]["refresh_token"].as_str().expect("No refresh token");
+    assert_ne!(first, second);
+    assert!(rotated_body["data"]["token"].is_string());
+
+    // Replaying the used token revokes the whole family, including the token it was rotated into
+    assert_eq!(refresh(&client, first).await.status(), 401);
+    assert_eq!(refresh(&client, second).await.status(), 401);
+}
+
+#[tokio::test]
+async fn test_logout_revokes_access_and_refresh_tokens() {
+    let client = reqwest::Client::new();
+    let login = bootstrap_admin_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+    let refresh_token = login["refresh_token"].as_str().expect("No refresh token");
+
+    let logout_response = client
+        .post("http://localhost:3000/api/auth/logout")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(logout_response.status(), 200);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+
+    assert_eq!(refresh(&client, refresh_token).await.status(), 401);
+}
+
+#[tokio::test]
+async fn test_logout_without_a_body() {
+    let client = reqwest::Client::new();
+    let token = bootstrap_admin_token(&client).await;
+
+    let logout_response = client
+        .post("http://localhost:3000/api/auth/logout")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(logout_response.status(), 200);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+}
+
+// Creates a worker profile as the admin, registers its account and logs in
+async fn new_worker_login(client: &reqwest::Client) -> serde_json::Value {
+    let admin_token = bootstrap_admin_token(client).await;
+    let email = format!("worker-{}@example.com", uuid::Uuid::new_v4());
+    let password = "Session-Worker-1";
+
+    let worker_response = client
+        .post("http://localhost:3000/api/workers")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "name": "Session Worker",
+            "email": email,
+            "skills": [],
+            "experience_years": 1
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let worker_body: serde_json::Value = worker_response.json().await.expect("Failed to parse JSON");
+
+    let register_response = client
+        .post("http://localhost:3000/api/auth/register")
+        .json(&json!({
+            "email": email,
+            "password": password,
+            "role": "worker",
+            "worker_id": worker_body["data"]["id"]
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(register_response.status(), 201);
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": email, "password": password }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 200);
+
+    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
+    login_body["data"].clone()
+}
+
+#[tokio::test]
+async fn test_logout_everywhere_ends_every_session() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+    let refresh_token = login["refresh_token"].as_str().expect("No refresh token");
+
+    // No pause: a token issued in the same second as the logout must stop working too
+    let logout_response = client
+        .post("http://localhost:3000/api/auth/logout-all")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(logout_response.status(), 200);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+
+    assert_eq!(refresh(&client, refresh_token).await.status(), 401);
+}
+
+#[tokio::test]
+async fn test_password_reset_request_does_not_reveal_accounts() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let known_email = login["user"]["email"].as_str().expect("No email found");
+
+    let mut bodies = Vec::new();
+    for email in [known_email, "nobody-here@example.com"] {
+        let response = client
+            .post("http://localhost:3000/api/auth/password-reset/request")
+            .json(&json!({ "email": email }))
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status(), 200);
+        bodies.push(response.text().await.expect("Failed to read body"));
+    }
+
+    assert_eq!(bodies[0], bodies[1]);
+}
+
+#[tokio::test]
+async fn test_password_reset_rejects_unknown_token() {
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post("http://localhost:3000/api/auth/password-reset/confirm")
+        .json(&json!({
+            "token": "not-a-real-token",
+            "new_password": "Reset-Password-1",
+            "confirm_password": "Reset-Password-1"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_new_accounts_start_unverified() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+
+    assert!(login["user"]["email_verified_at"].is_null());
+
+    let verify_response = client
+        .post("http://localhost:3000/api/auth/verify-email")
+        .json(&json!({ "token": "not-a-real-token" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(verify_response.status(), 400);
+
+    let resend_response = client
+        .post("http://localhost:3000/api/auth/verify-email/resend")
+        .json(&json!({ "email": login["user"]["email"] }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(resend_response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_mfa_enrollment_and_two_step_login() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+    let email = login["user"]["email"].as_str().expect("No email found");
+
+    let setup_response = client
+        .post("http://localhost:3000/api/auth/mfa/setup")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(setup_response.status(), 200);
+
+    let setup_body: serde_json::Value = setup_response.json().await.expect("Failed to parse JSON");
+    let secret = setup_body["data"]["secret"].as_str().expect("No secret");
+    let code = recruitment_backend::totp::code_at(secret, chrono::Utc::now().timestamp()).expect("Valid secret");
+
+    let confirm_response = client
+        .post("http://localhost:3000/api/auth/mfa/confirm")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "code": code }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(confirm_response.status(), 200);
+
+    let confirm_body: serde_json::Value = confirm_response.json().await.expect("Failed to parse JSON");
+    let recovery_code = confirm_body["data"]["recovery_codes"][0].as_str().expect("No recovery codes").to_string();
+
+    // The password alone now only yields a challenge
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": email, "password": "Session-Worker-1" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 200);
+
+    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(login_body["data"]["mfa_required"], true);
+    assert!(login_body["data"]["token"].is_null());
+    let mfa_token = login_body["data"]["mfa_token"].as_str().expect("No MFA token");
+
+    // The challenge is not an access token
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", mfa_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+
+    for (attempt, expected_status) in [(1, 200), (2, 401)] {
+        let verify_response = client
+            .post("http://localhost:3000/api/auth/mfa/verify")
+            .json(&json!({ "mfa_token": mfa_token, "code": recovery_code }))
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(verify_response.status(), expected_status, "recovery code use #{}", attempt);
+    }
+}
+
+#[tokio::test]
+async fn test_magic_link_login_rejects_unknown_tokens() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+
+    let request_response = client
+        .post("http://localhost:3000/api/auth/magic-link/request")
+        .json(&json!({ "email": login["user"]["email"] }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(request_response.status(), 200);
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/magic-link/login")
+        .json(&json!({ "token": "not-a-real-token" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_api_key_is_limited_to_its_scopes() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "company_name": "Integration ATS Co",
+            "email": format!("ats-{}@example.com", uuid::Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+    let client_id = client_body["data"]["id"].as_str().expect("No client id").to_string();
+
+    let key_response = client
+        .post(format!("http://localhost:3000/api/clients/{}/api-keys", client_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "name": "ATS sync", "scopes": ["jobs:read"] }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(key_response.status(), 201);
+
+    let key_body: serde_json::Value = key_response.json().await.expect("Failed to parse JSON");
+    let key = key_body["data"]["key"].as_str().expect("No key").to_string();
+    let key_id = key_body["data"]["id"].as_str().expect("No key id").to_string();
+    assert!(key.starts_with(&format!("rak_{}_", key_body["data"]["prefix"].as_str().unwrap())));
+
+    let list_response = client
+        .get("http://localhost:3000/api/jobs")
+        .header("X-Api-Key", &key)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(list_response.status(), 200);
+
+    // Out of scope, and account routes never take keys
+    let create_response = client
+        .post("http://localhost:3000/api/jobs")
+        .header("X-Api-Key", &key)
+        .json(&json!({ "client_id": client_id, "title": "Not allowed" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(create_response.status(), 403);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("X-Api-Key", &key)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+
+    let revoke_response = client
+        .delete(format!("http://localhost:3000/api/clients/{}/api-keys/{}", client_id, key_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(revoke_response.status(), 200);
+
+    let revoked_response = client
+        .get("http://localhost:3000/api/jobs")
+        .header("X-Api-Key", &key)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(revoked_response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_api_key_expiry_can_be_removed() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "company_name": "Expiring Key Co",
+            "email": format!("expiring-{}@example.com", uuid::Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+    let client_id = client_body["data"]["id"].as_str().expect("No client id").to_string();
+
+    let key_response = client
+        .post(format!("http://localhost:3000/api/clients/{}/api-keys", client_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "name": "ATS sync",
+            "scopes": ["jobs:read"],
+            "expires_at": (chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339()
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(key_response.status(), 201);
+
+    let key_body: serde_json::Value = key_response.json().await.expect("Failed to parse JSON");
+    let key_id = key_body["data"]["id"].as_str().expect("No key id").to_string();
+    let key_url = format!("http://localhost:3000/api/clients/{}/api-keys/{}", client_id, key_id);
+
+    // Leaving the field out keeps the expiry
+    let rename_response = client
+        .put(&key_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "name": "ATS nightly sync" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(rename_response.status(), 200);
+
+    let rename_body: serde_json::Value = rename_response.json().await.expect("Failed to parse JSON");
+    assert!(rename_body["data"]["expires_at"].is_string());
+
+    let clear_response = client
+        .put(&key_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "expires_at": null }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(clear_response.status(), 200);
+
+    let clear_body: serde_json::Value = clear_response.json().await.expect("Failed to parse JSON");
+    assert!(clear_body["data"]["expires_at"].is_null());
+}
+
+#[tokio::test]
+async fn test_api_key_stops_working_when_its_owner_is_demoted() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let owner_email = format!("key-owner-{}@example.com", uuid::Uuid::new_v4());
+
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "company_name": "Key Owner Co", "email": owner_email }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+    let client_id = client_body["data"]["id"].as_str().expect("No client id").to_string();
+
+    let register_response = client
+        .post("http://localhost:3000/api/auth/register")
+        .json(&json!({
+            "email": owner_email,
+            "password": "Key-Owner-1",
+            "role": "client",
+            "client_id": client_id
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(register_response.status(), 201);
+
+    let register_body: serde_json::Value = register_response.json().await.expect("Failed to parse JSON");
+    let owner_id = register_body["data"]["id"].as_str().expect("No user id").to_string();
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": owner_email, "password": "Key-Owner-1" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 200);
+
+    let login_body: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
+    let owner_token = login_body["data"]["token"].as_str().expect("No token found");
+
+    let key_response = client
+        .post(format!("http://localhost:3000/api/clients/{}/api-keys", client_id))
+        .header("Authorization", format!("Bearer {}", owner_token))
+        .json(&json!({ "name": "ATS sync", "scopes": ["jobs:read"] }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(key_response.status(), 201);
+
+    let key_body: serde_json::Value = key_response.json().await.expect("Failed to parse JSON");
+    let key = key_body["data"]["key"].as_str().expect("No key").to_string();
+
+    let list_response = client
+        .get("http://localhost:3000/api/jobs")
+        .header("X-Api-Key", &key)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(list_response.status(), 200);
+
+    // Another owner takes over, so the creator can step down to recruiter
+    let other_login = new_worker_login(&client).await;
+    let other_id = other_login["user"]["id"].as_str().expect("No user id");
+
+    let promote_response = client
+        .put(format!("http://localhost:3000/api/users/{}", other_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "role": "client", "client_id": client_id, "team_role": "owner" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(promote_response.status(), 200);
+
+    let demote_response = client
+        .put(format!("http://localhost:3000/api/users/{}", owner_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "team_role": "recruiter" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(demote_response.status(), 200);
+
+    let demoted_response = client
+        .get("http://localhost:3000/api/jobs")
+        .header("X-Api-Key", &key)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(demoted_response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_sso_login_provisions_client_user() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let idp = mock_idp::MockIdp::start().await;
+
+    let domain = format!("sso-{}.example", uuid::Uuid::new_v4().simple());
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "company_name": "SSO Co", "email": format!("hr@{}", domain) }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+    let client_id = client_body["data"]["id"].as_str().expect("No client id").to_string();
+
+    let config_response = client
+        .put(format!("http://localhost:3000/api/clients/{}/sso", client_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "issuer": idp.issuer,
+            "oidc_client_id": mock_idp::CLIENT_ID,
+            "oidc_client_secret": mock_idp::CLIENT_SECRET,
+            "allowed_email_domains": [domain]
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(config_response.status(), 200);
+
+    let config_body: serde_json::Value = config_response.json().await.expect("Failed to parse JSON");
+    assert!(config_body["data"].get("oidc_client_secret").is_none());
+
+    // The browser is redirected to the provider; read what it was sent with
+    let browser = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap();
+
+    let start_response = browser
+        .get(format!("http://localhost:3000/api/auth/sso/{}/start", client_id))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert!(start_response.status().is_redirection());
+
+    let location = start_response.headers()["location"].to_str().unwrap().to_string();
+    assert!(location.starts_with(&format!("{}/authorize?", idp.issuer)));
+
+    let params: HashMap<String, String> = reqwest::Url::parse(&location)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect();
+    assert_eq!(params["code_challenge_method"], "S256");
+
+    let email = format!("jane@{}", domain);
+    let code = idp.authorize(&params["code_challenge"], &params["nonce"], &email, true);
+
+    let callback_url = format!(
+        "http://localhost:3000/api/auth/sso/callback?code={}&state={}",
+        code, params["state"]
+    );
+    let callback_response = browser
+        .get(&callback_url)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(callback_response.status(), 200);
+
+    let callback_body: serde_json::Value = callback_response.json().await.expect("Failed to parse JSON");
+    assert!(callback_body["data"]["token"].is_string());
+    assert_eq!(callback_body["data"]["user"]["email"], email);
+    assert_eq!(callback_body["data"]["user"]["role"], "client");
+    assert_eq!(callback_body["data"]["user"]["client_id"], client_id.as_str());
+
+    // A state works once
+    let replay_response = browser
+        .get(&callback_url)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(replay_response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_access_tokens_verify_against_jwks() {
+    let client = reqwest::Client::new();
+    let token = bootstrap_admin_token(&client).await;
+
+    let jwks_response = client
+        .get("http://localhost:3000/.well-known/jwks.json")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(jwks_response.status(), 200);
+
+    let jwks: jsonwebtoken::jwk::JwkSet = jwks_response.json().await.expect("Failed to parse JWKS");
+    let header = jsonwebtoken::decode_header(&token).expect("Invalid token");
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+
+    let jwk = jwks.find(&header.kid.expect("No kid")).expect("Signing key not published");
+    let key = jsonwebtoken::DecodingKey::from_jwk(jwk).expect("Invalid JWK");
+    let claims = jsonwebtoken::decode::<serde_json::Value>(
+        &token,
+        &key,
+        &jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::EdDSA),
+    )
+    .expect("Token does not verify")
+    .claims;
+    assert_eq!(claims["email"], bootstrap_admin_credentials().0);
+}
+
+#[tokio::test]
+async fn test_client_invitation_lifecycle() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "company_name": "Team Co",
+            "email": format!("team-{}@example.com", uuid::Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+    let client_id = client_body["data"]["id"].as_str().expect("No client id").to_string();
+    let invitations_url = format!("http://localhost:3000/api/clients/{}/invitations", client_id);
+
+    let bad_role_response = client
+        .post(&invitations_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "email": "someone@example.com", "team_role": "superuser" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(bad_role_response.status(), 400);
+
+    let invite_response = client
+        .post(&invitations_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "email": format!("manager-{}@example.com", uuid::Uuid::new_v4()),
+            "team_role": "hiring_manager"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(invite_response.status(), 201);
+
+    let invite_body: serde_json::Value = invite_response.json().await.expect("Failed to parse JSON");
+    let invitation_id = invite_body["data"]["id"].as_str().expect("No invitation id").to_string();
+    assert_eq!(invite_body["data"]["team_role"], "hiring_manager");
+    assert!(invite_body["data"].get("token_hash").is_none());
+
+    let list_response = client
+        .get(&invitations_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let list_body: serde_json::Value = list_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(list_body["data"].as_array().unwrap().len(), 1);
+
+    let revoke_response = client
+        .delete(format!("{}/{}", invitations_url, invitation_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(revoke_response.status(), 200);
+
+    let accept_response = client
+        .post("http://localhost:3000/api/auth/invitations/accept")
+        .json(&json!({ "token": "not-a-real-token", "password": "Invited-User-1" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(accept_response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_workers_cannot_manage_client_teams() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+
+    let response = client
+        .get(format!("http://localhost:3000/api/clients/{}/invitations", uuid::Uuid::new_v4()))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), 403);
+}
+
+#[tokio::test]
+async fn test_admin_user_management() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let login = new_worker_login(&client).await;
+    let worker_token = login["token"].as_str().expect("No token found");
+    let user_id = login["user"]["id"].as_str().expect("No user id");
+    let email = login["user"]["email"].as_str().expect("No email");
+    assert!(login["user"].get("password_hash").is_none());
+
+    let list_response = client
+        .get("http://localhost:3000/api/users")
+        .query(&[("search", email)])
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(list_response.status(), 200);
+
+    let list_body: serde_json::Value = list_response.json().await.expect("Failed to parse JSON");
+    let users = list_body["data"]["items"].as_array().expect("No users");
+    assert_eq!(users.len(), 1);
+    assert!(users[0]["last_login_at"].is_string());
+    assert!(users[0].get("password_hash").is_none());
+
+    // Workers are not admins
+    let forbidden_response = client
+        .get("http://localhost:3000/api/users")
+        .header("Authorization", format!("Bearer {}", worker_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(forbidden_response.status(), 403);
+
+    let deactivate_response = client
+        .post(format!("http://localhost:3000/api/users/{}/deactivate", user_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(deactivate_response.status(), 200);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", worker_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+
+    let reactivate_response = client
+        .post(format!("http://localhost:3000/api/users/{}/reactivate", user_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(reactivate_response.status(), 200);
+
+    let reset_response = client
+        .post(format!("http://localhost:3000/api/users/{}/password-reset", user_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(reset_response.status(), 200);
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": email, "password": "Session-Worker-1" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 403);
+}
+
+#[tokio::test]
+async fn test_admins_cannot_remove_an_organizations_last_owner() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let owner_email = format!("last-owner-{}@example.com", uuid::Uuid::new_v4());
+
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "company_name": "Last Owner Co", "email": owner_email }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+    let client_id = client_body["data"]["id"].as_str().expect("No client id").to_string();
+
+    let register_response = client
+        .post("http://localhost:3000/api/auth/register")
+        .json(&json!({
+            "email": owner_email,
+            "password": "Last-Owner-1",
+            "role": "client",
+            "client_id": client_id
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(register_response.status(), 201);
+
+    let register_body: serde_json::Value = register_response.json().await.expect("Failed to parse JSON");
+    let owner_id = register_body["data"]["id"].as_str().expect("No user id").to_string();
+
+    let demote_response = client
+        .put(format!("http://localhost:3000/api/users/{}", owner_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "team_role": "viewer" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(demote_response.status(), 409);
+
+    let promote_response = client
+        .put(format!("http://localhost:3000/api/users/{}", owner_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "role": "admin" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(promote_response.status(), 409);
+
+    let deactivate_response = client
+        .post(format!("http://localhost:3000/api/users/{}/deactivate", owner_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(deactivate_response.status(), 409);
+}
+
+#[tokio::test]
+async fn test_user_search_matches_wildcards_literally() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+
+    let response = client
+        .get("http://localhost:3000/api/users")
+        .query(&[("search", "%_")])
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), 200);
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["data"]["pagination"]["total"], 0);
+}
+
+#[tokio::test]
+async fn test_admin_impersonation_is_limited_and_audited() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let login = new_worker_login(&client).await;
+    let user_id = login["user"]["id"].as_str().expect("No user id");
+
+    let impersonate_response = client
+        .post(format!("http://localhost:3000/api/users/{}/impersonate", user_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(impersonate_response.status(), 201);
+
+    let impersonate_body: serde_json::Value = impersonate_response.json().await.expect("Failed to parse JSON");
+    let token = impersonate_body["data"]["token"].as_str().expect("No token found");
+    assert!(impersonate_body["data"].get("refresh_token").is_none());
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 200);
+
+    let me_body: serde_json::Value = me_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(me_body["data"]["id"], user_id);
+    assert!(me_body["data"]["impersonated_by"].is_string());
+
+    let password_response = client
+        .post("http://localhost:3000/api/auth/update-password")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "current_password": "Session-Worker-1",
+            "new_password": "Hijacked-Password-1",
+            "confirm_password": "Hijacked-Password-1"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(password_response.status(), 403);
+
+    let log_response = client
+        .get("http://localhost:3000/api/impersonation-log")
+        .query(&[("user_id", user_id)])
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(log_response.status(), 200);
+
+    // The start, the profile view and the refused password change
+    let log_body: serde_json::Value = log_response.json().await.expect("Failed to parse JSON");
+    let entries = log_body["data"]["items"].as_array().expect("No log entries");
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0]["status_code"], 403);
+}
+
+#[tokio::test]
+async fn test_invalidating_sessions_ends_impersonation_tokens() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let login = new_worker_login(&client).await;
+    let user_id = login["user"]["id"].as_str().expect("No user id");
+
+    let impersonate_response = client
+        .post(format!("http://localhost:3000/api/users/{}/impersonate", user_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(impersonate_response.status(), 201);
+
+    let impersonate_body: serde_json::Value = impersonate_response.json().await.expect("Failed to parse JSON");
+    let token = impersonate_body["data"]["token"].as_str().expect("No token found");
+
+    let reset_response = client
+        .post(format!("http://localhost:3000/api/users/{}/password-reset", user_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(reset_response.status(), 200);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_common_passwords_are_refused() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+
+    let response = client
+        .post("http://localhost:3000/api/auth/update-password")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "current_password": "Session-Worker-1",
+            "new_password": "P@ssw0rd1",
+            "confirm_password": "P@ssw0rd1"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), 400);
+
+    let body: serde_json::Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["error"], "This password is too common, choose another one");
+}
+
+#[tokio::test]
+async fn test_passkey_registration_and_login() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+    let mut authenticator = soft_authenticator::SoftAuthenticator::new("localhost", "http://localhost:3000");
+
+    let start_response = client
+        .post("http://localhost:3000/api/auth/passkeys/register/start")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(start_response.status(), 200);
+    let options: serde_json::Value = start_response.json().await.expect("Failed to parse JSON");
+
+    let finish_response = client
+        .post("http://localhost:3000/api/auth/passkeys/register/finish")
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "name": "Test key",
+            "credential": authenticator.create(&options["data"])
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(finish_response.status(), 201);
+    let passkey: serde_json::Value = finish_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(passkey["data"]["name"], "Test key");
+    assert!(passkey["data"].get("public_key").is_none());
+
+    // Log in without an email address
+    let start_response = client
+        .post("http://localhost:3000/api/auth/passkeys/login/start")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(start_response.status(), 200);
+    let options: serde_json::Value = start_response.json().await.expect("Failed to parse JSON");
+    let assertion = authenticator.get(&options["data"]);
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/passkeys/login/finish")
+        .json(&json!({ "credential": assertion }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 200);
+    let session: serde_json::Value = login_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(session["data"]["user"]["id"], login["user"]["id"]);
+    assert!(session["data"]["refresh_token"].is_string());
+
+    // Each challenge works once
+    let replay_response = client
+        .post("http://localhost:3000/api/auth/passkeys/login/finish")
+        .json(&json!({ "credential": assertion }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(replay_response.status(), 401);
+
+    let list_response = client
+        .get("http://localhost:3000/api/auth/passkeys")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let list: serde_json::Value = list_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(list["data"].as_array().map(Vec::len), Some(1));
+    assert!(list["data"][0]["last_used_at"].is_string());
+
+    let delete_response = client
+        .delete(format!("http://localhost:3000/api/auth/passkeys/{}", passkey["data"]["id"].as_str().unwrap()))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(delete_response.status(), 200);
+
+    // A removed passkey no longer signs in
+    let options: serde_json::Value = client
+        .post("http://localhost:3000/api/auth/passkeys/login/start")
+        .send()
+        .await
+        .expect("Failed to execute request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/passkeys/login/finish")
+        .json(&json!({ "credential": authenticator.get(&options["data"]) }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 401);
+}
+
+#[tokio::test]
+async fn test_sessions_can_be_listed_and_revoked() {
+    let client = reqwest::Client::new();
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+
+    // A second device
+    let other_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .header("User-Agent", "Session Test Phone")
+        .json(&json!({ "email": login["user"]["email"], "password": "Session-Worker-1" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(other_response.status(), 200);
+    let other: serde_json::Value = other_response.json().await.expect("Failed to parse JSON");
+    let other_token = other["data"]["token"].as_str().expect("No token found");
+    let other_refresh = other["data"]["refresh_token"].as_str().expect("No refresh token");
+
+    let list_response = client
+        .get("http://localhost:3000/api/auth/sessions")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(list_response.status(), 200);
+    let list: serde_json::Value = list_response.json().await.expect("Failed to parse JSON");
+    let sessions = list["data"].as_array().expect("No sessions");
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions.iter().filter(|session| session["current"] == true).count(), 1);
+
+    let phone = sessions
+        .iter()
+        .find(|session| session["user_agent"] == "Session Test Phone")
+        .expect("Second device not listed");
+    assert_eq!(phone["current"], false);
+    assert!(phone["ip_address"].is_string());
+
+    let revoke_response = client
+        .delete(format!("http://localhost:3000/api/auth/sessions/{}", phone["id"].as_str().unwrap()))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(revoke_response.status(), 200);
+
+    // The revoked device is signed out at once, the caller is not
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", other_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 401);
+    assert_eq!(refresh(&client, other_refresh).await.status(), 401);
+
+    let me_response = client
+        .get("http://localhost:3000/api/auth/me")
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(me_response.status(), 200);
+
+    // Another user's session, or one already revoked, is not found
+    let again_response = client
+        .delete(format!("http://localhost:3000/api/auth/sessions/{}", phone["id"].as_str().unwrap()))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(again_response.status(), 404);
+}
+
+// Creates a client and an open job for it as the admin, returning the job id
+async fn create_open_job(client: &reqwest::Client, admin_token: &str) -> String {
+    let client_response = client
+        .post("http://localhost:3000/api/clients")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "company_name": "Hiring Co",
+            "email": format!("hiring-{}@example.com", uuid::Uuid::new_v4())
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(client_response.status(), 201);
+    let client_body: serde_json::Value = client_response.json().await.expect("Failed to parse JSON");
+
+    let job_response = client
+        .post("http://localhost:3000/api/jobs")
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "client_id": client_body["data"]["id"],
+            "title": "Rust Developer",
+            "description": "Backend services in Rust",
+            "requirements": ["Rust"],
+            "location": "Remote",
+            "is_active": true
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(job_response.status(), 201);
+
+    let job_body: serde_json::Value = job_response.json().await.expect("Failed to parse JSON");
+    job_body["data"]["id"].as_str().expect("No job id").to_string()
+}
+
+#[tokio::test]
+async fn test_worker_applies_once_and_withdraws() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let job_id = create_open_job(&client, &admin_token).await;
+    let applications_url = format!("http://localhost:3000/api/jobs/{}/applications", job_id);
+
+    let login = new_worker_login(&client).await;
+    let token = login["token"].as_str().expect("No token found");
+
+    let apply_response = client
+        .post(&applications_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "cover_letter": "  I write Rust.  " }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(apply_response.status(), 201);
+
+    let apply_body: serde_json::Value = apply_response.json().await.expect("Failed to parse JSON");
+    let application_id = apply_body["data"]["id"].as_str().expect("No application id").to_string();
+    assert_eq!(apply_body["data"]["status"], "pending");
+    assert_eq!(apply_body["data"]["worker_id"], login["user"]["worker_id"]);
+    assert_eq!(apply_body["data"]["cover_letter"], "I write Rust.");
+
+    // One application per worker and job
+    let again_response = client
+        .post(&applications_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(again_response.status(), 409);
+
+    // Only the linked worker applies or withdraws
+    let admin_apply_response = client
+        .post(&applications_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(admin_apply_response.status(), 403);
+
+    let withdraw_url = format!("http://localhost:3000/api/applications/{}/withdraw", application_id);
+    let other = new_worker_login(&client).await;
+    let other_response = client
+        .post(&withdraw_url)
+        .header("Authorization", format!("Bearer {}", other["token"].as_str().unwrap()))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(other_response.status(), 404);
+
+    let withdraw_response = client
+        .post(&withdraw_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(withdraw_response.status(), 200);
+
+    let withdraw_body: serde_json::Value = withdraw_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(withdraw_body["data"]["status"], "withdrawn");
+
+    // Withdrawn is terminal
+    let terminal_response = client
+        .post(&withdraw_url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(terminal_response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_moving_through_stages_advances_the_status() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let job_id = create_open_job(&client, &admin_token).await;
+
+    let login = new_worker_login(&client).await;
+    let apply_response = client
+        .post(format!("http://localhost:3000/api/jobs/{}/applications", job_id))
+        .header("Authorization", format!("Bearer {}", login["token"].as_str().unwrap()))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(apply_response.status(), 201);
+    let apply_body: serde_json::Value = apply_response.json().await.expect("Failed to parse JSON");
+    let application_id = apply_body["data"]["id"].as_str().expect("No application id").to_string();
+
+    let stages_response = client
+        .get(format!("http://localhost:3000/api/jobs/{}/stages", job_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let stages_body: serde_json::Value = stages_response.json().await.expect("Failed to parse JSON");
+    let stage_id = |name: &str| {
+        stages_body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|stage| stage["name"] == name)
+            .map(|stage| stage["id"].clone())
+            .expect("Missing default stage")
+    };
+
+    let stage_url = format!("http://localhost:3000/api/applications/{}/stage", application_id);
+    let move_to = |stage: serde_json::Value| {
+        client
+            .post(&stage_url)
+            .header("Authorization", format!("Bearer {}", admin_token))
+            .json(&json!({ "stage_id": stage }))
+            .send()
+    };
+
+    let interview_response = move_to(stage_id("Interview")).await.expect("Failed to execute request");
+    assert_eq!(interview_response.status(), 200);
+    let interview_body: serde_json::Value = interview_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(interview_body["data"]["status"], "interview");
+
+    // Statuses never go back, nor do stages of another job count
+    let back_response = move_to(stage_id("Screening")).await.expect("Failed to execute request");
+    assert_eq!(back_response.status(), 400);
+
+    let foreign_response = move_to(json!(uuid::Uuid::new_v4())).await.expect("Failed to execute request");
+    assert_eq!(foreign_response.status(), 400);
+
+    let hired_response = move_to(stage_id("Hired")).await.expect("Failed to execute request");
+    assert_eq!(hired_response.status(), 200);
+    let hired_body: serde_json::Value = hired_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(hired_body["data"]["status"], "hired");
+    assert_eq!(hired_body["data"]["stage_id"], stage_id("Hired"));
+
+    let history_response = client
+        .get(format!("http://localhost:3000/api/applications/{}/history", application_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let history_body: serde_json::Value = history_response.json().await.expect("Failed to parse JSON");
+    let statuses: Vec<&str> = history_body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["to_status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["pending", "reviewing", "interview", "offer", "hired"]);
+}
+
+// An application moved to interview as the admin, with the applying worker's token
+async fn application_at_interview(client: &reqwest::Client, admin_token: &str) -> (String, String) {
+    let job_id = create_open_job(client, admin_token).await;
+    let login = new_worker_login(client).await;
+    let worker_token = login["token"].as_str().expect("No token found").to_string();
+
+    let apply_response = client
+        .post(format!("http://localhost:3000/api/jobs/{}/applications", job_id))
+        .header("Authorization", format!("Bearer {}", worker_token))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let apply_body: serde_json::Value = apply_response.json().await.expect("Failed to parse JSON");
+    let application_id = apply_body["data"]["id"].as_str().expect("No application id").to_string();
+
+    for status in ["reviewing", "interview"] {
+        let status_response = client
+            .post(format!("http://localhost:3000/api/applications/{}/status", application_id))
+            .header("Authorization", format!("Bearer {}", admin_token))
+            .json(&json!({ "status": status }))
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(status_response.status(), 200);
+    }
+
+    (application_id, worker_token)
+}
+
+async fn draft_offer(client: &reqwest::Client, admin_token: &str, application_id: &str, expires_at: chrono::DateTime<chrono::Utc>) -> String {
+    let offer_response = client
+        .post(format!("http://localhost:3000/api/applications/{}/offers", application_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({
+            "salary": 90000,
+            "currency": "EUR",
+            "start_date": "2031-01-05",
+            "expires_at": expires_at,
+            "notes": "Client would go up to 100k"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(offer_response.status(), 201);
+
+    let offer_body: serde_json::Value = offer_response.json().await.expect("Failed to parse JSON");
+    offer_body["data"]["id"].as_str().expect("No offer id").to_string()
+}
+
+#[tokio::test]
+async fn test_candidates_only_see_sent_offers() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let (application_id, worker_token) = application_at_interview(&client, &admin_token).await;
+    let offer_id = draft_offer(&client, &admin_token, &application_id, chrono::Utc::now() + chrono::Duration::days(7)).await;
+
+    let get_as_worker = |url: String| {
+        client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", worker_token))
+            .send()
+    };
+    let offers_url = format!("http://localhost:3000/api/applications/{}/offers", application_id);
+    let offer_url = format!("http://localhost:3000/api/offers/{}", offer_id);
+
+    let list_response = get_as_worker(offers_url.clone()).await.expect("Failed to execute request");
+    assert_eq!(list_response.status(), 200);
+    let list_body: serde_json::Value = list_response.json().await.expect("Failed to parse JSON");
+    assert!(list_body["data"].as_array().unwrap().is_empty());
+
+    let draft_response = get_as_worker(offer_url.clone()).await.expect("Failed to execute request");
+    assert_eq!(draft_response.status(), 404);
+
+    let send_response = client
+        .post(format!("{}/send", offer_url))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(send_response.status(), 200);
+
+    let list_response = get_as_worker(offers_url).await.expect("Failed to execute request");
+    let list_body: serde_json::Value = list_response.json().await.expect("Failed to parse JSON");
+    let offers = list_body["data"].as_array().unwrap();
+    assert_eq!(offers.len(), 1);
+    assert_eq!(offers[0]["status"], "sent");
+    assert!(offers[0]["notes"].is_null());
+
+    let revisions_response = get_as_worker(format!("{}/revisions", offer_url)).await.expect("Failed to execute request");
+    assert_eq!(revisions_response.status(), 403);
+
+    // The agency still sees everything
+    let admin_response = client
+        .get(&offer_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let admin_body: serde_json::Value = admin_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(admin_body["data"]["notes"], "Client would go up to 100k");
+}
+
+#[tokio::test]
+async fn test_rejecting_an_application_rescinds_its_offers() {
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let (application_id, _) = application_at_interview(&client, &admin_token).await;
+    let expires_at = chrono::Utc::now() + chrono::Duration::days(7);
+    let sent_offer_id = draft_offer(&client, &admin_token, &application_id, expires_at).await;
+    let draft_offer_id = draft_offer(&client, &admin_token, &application_id, expires_at).await;
+
+    let send_response = client
+        .post(format!("http://localhost:3000/api/offers/{}/send", sent_offer_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(send_response.status(), 200);
+
+    let reject_response = client
+        .post(format!("http://localhost:3000/api/applications/{}/status", application_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .json(&json!({ "status": "rejected", "reason": "Client chose another candidate" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(reject_response.status(), 200);
+
+    for offer_id in [&sent_offer_id, &draft_offer_id] {
+        let offer_response = client
+            .get(format!("http://localhost:3000/api/offers/{}", offer_id))
+            .header("Authorization", format!("Bearer {}", admin_token))
+            .send()
+            .await
+            .expect("Failed to execute request");
+        let offer_body: serde_json::Value = offer_response.json().await.expect("Failed to parse JSON");
+        assert_eq!(offer_body["data"]["status"], "rescinded");
+    }
+
+    // Nothing can be sent on a closed application
+    let late_send_response = client
+        .post(format!("http://localhost:3000/api/offers/{}/send", draft_offer_id))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(late_send_response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_expiry_sweep_expires_overdue_sent_offers() {
+    dotenv::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = sqlx::PgPool::connect(&database_url).await.expect("Failed to connect to database");
+
+    let client = reqwest::Client::new();
+    let admin_token = bootstrap_admin_token(&client).await;
+    let (application_id, _) = application_at_interview(&client, &admin_token).await;
+    let offer_id = draft_offer(&client, &admin_token, &application_id, chrono::Utc::now() + chrono::Duration::seconds(2)).await;
+    let offer_url = format!("http://localhost:3000/api/offers/{}", offer_id);
+
+    let send_response = client
+        .post(format!("{}/send", offer_url))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(send_response.status(), 200);
+
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+
+    let expired = recruitment_backend::handlers::expire_overdue_offers(&pool)
+        .await
+        .expect("Sweep failed");
+    // The server's own sweep may have got there first
+    let offer_response = client
+        .get(&offer_url)
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let offer_body: serde_json::Value = offer_response.json().await.expect("Failed to parse JSON");
+    assert_eq!(offer_body["data"]["status"], "expired", "sweep expired {} offers", expired);
+
+    let revisions_response = client
+        .get(format!("{}/revisions", offer_url))
+        .header("Authorization", format!("Bearer {}", admin_token))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    let revisions_body: serde_json::Value = revisions_response.json().await.expect("Failed to parse JSON");
+    let events: Vec<&str> = revisions_body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|revision| revision["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(events, ["created", "sent", "expired"]);
+}
+
+#[tokio::test]
+async fn test_public_registration_never_creates_admins() {
+    let client = reqwest::Client::new();
+    let email = format!("new-admin-{}@example.com", uuid::Uuid::new_v4());
+
+    let register_response = client
+        .post("http://localhost:3000/api/auth/register")
+        .json(&json!({
+            "email": email,
+            "password": "Would-Be-Admin-1",
+            "role": "admin"
+        }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(register_response.status(), 403);
+
+    let login_response = client
+        .post("http://localhost:3000/api/auth/login")
+        .json(&json!({ "email": email, "password": "Would-Be-Admin-1" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(login_response.status(), 401);
+}
@@ -0,0 +1,123 @@
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+use recruitment_backend::throttle::{LoginThrottle, ThrottlePolicy};
+
+const EMAIL: &str = "candidate@example.com";
+
+fn ip(last: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(10, 0, 0, last))
+}
+
+fn policy() -> ThrottlePolicy {
+    ThrottlePolicy {
+        free_attempts: 2,
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(8),
+        lockout_after: 6,
+        lockout_duration: Duration::from_secs(600),
+        reset_after: Duration::from_secs(900),
+    }
+}
+
+fn lenient() -> ThrottlePolicy {
+    ThrottlePolicy { free_attempts: 100, lockout_after: 1000, ..policy() }
+}
+
+#[test]
+fn test_free_attempts_are_not_delayed() {
+    let throttle = LoginThrottle::new(policy(), lenient());
+    let now = Instant::now();
+
+    throttle.record_failure(EMAIL, ip(1), now);
+    throttle.record_failure(EMAIL, ip(1), now);
+
+    assert_eq!(throttle.check(EMAIL, ip(1), now), Ok(()));
+}
+
+#[test]
+fn test_backoff_doubles_and_is_capped() {
+    let throttle = LoginThrottle::new(policy(), lenient());
+    let start = Instant::now();
+    let mut expected = Vec::new();
+
+    for failure in 1..=5u64 {
+        let now = start + Duration::from_secs(failure * 100);
+        throttle.record_failure(EMAIL, ip(1), now);
+        expected.push(throttle.check(EMAIL, ip(1), now).err());
+    }
+
+    assert_eq!(expected, vec![
+        None,
+        None,
+        Some(Duration::from_secs(1)),
+        Some(Duration::from_secs(2)),
+        Some(Duration::from_secs(4)),
+    ]);
+
+    // Capped at max_delay
+    let throttle = LoginThrottle::new(ThrottlePolicy { lockout_after: 100, ..policy() }, lenient());
+    for _ in 0..20 {
+        throttle.record_failure(EMAIL, ip(1), start);
+    }
+    assert_eq!(throttle.check(EMAIL, ip(1), start), Err(Duration::from_secs(8)));
+}
+
+#[test]
+fn test_lockout_after_repeated_failures_then_expires() {
+    let throttle = LoginThrottle::new(policy(), lenient());
+    let now = Instant::now();
+
+    for _ in 0..6 {
+        throttle.record_failure(EMAIL, ip(1), now);
+    }
+
+    // Locked for every IP, not just the one that failed
+    assert_eq!(throttle.check(EMAIL, ip(2), now), Err(Duration::from_secs(600)));
+    assert_eq!(throttle.check(EMAIL, ip(2), now + Duration::from_secs(600)), Ok(()));
+}
+
+#[test]
+fn test_admin_unlock_and_success_clear_the_account() {
+    let throttle = LoginThrottle::new(policy(), lenient());
+    let now = Instant::now();
+
+    for _ in 0..6 {
+        throttle.record_failure(EMAIL, ip(1), now);
+    }
+    throttle.unlock_account("Candidate@Example.com");
+    assert_eq!(throttle.check(EMAIL, ip(1), now), Ok(()));
+
+    for _ in 0..3 {
+        throttle.record_failure(EMAIL, ip(1), now);
+    }
+    throttle.record_success(EMAIL);
+    assert_eq!(throttle.check(EMAIL, ip(1), now), Ok(()));
+}
+
+#[test]
+fn test_ip_is_throttled_across_accounts() {
+    let throttle = LoginThrottle::new(lenient(), policy());
+    let now = Instant::now();
+
+    for n in 0..3 {
+        throttle.record_failure(&format!("user{}@example.com", n), ip(7), now);
+    }
+
+    assert_eq!(throttle.check("someone-else@example.com", ip(7), now), Err(Duration::from_secs(1)));
+    assert_eq!(throttle.check("someone-else@example.com", ip(8), now), Ok(()));
+}
+
+#[test]
+fn test_failures_are_forgotten_after_quiet_period() {
+    let throttle = LoginThrottle::new(policy(), lenient());
+    let now = Instant::now();
+
+    for _ in 0..5 {
+        throttle.record_failure(EMAIL, ip(1), now);
+    }
+
+    let later = now + Duration::from_secs(900);
+    throttle.record_failure(EMAIL, ip(1), later);
+    assert_eq!(throttle.check(EMAIL, ip(1), later), Ok(()));
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use recruitment_backend::mailer::{deliver, mailer_from_env, Email, Mailer, OutboxMailer};
+use uuid::Uuid;
+
+fn outbox_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("outbox-{}", Uuid::new_v4()))
+}
+
+fn email(subject: &str) -> Email {
+    Email {
+        to: "candidate@example.com".to_string(),
+        subject: subject.to_string(),
+        body: "Use this link: http://localhost:3000/reset-password?token=abc".to_string(),
+    }
+}
+
+#[test]
+fn test_outbox_mailer_writes_one_file_per_email() {
+    let dir = outbox_dir();
+    let mailer = OutboxMailer::new(&dir);
+
+    mailer.send(&email("First")).unwrap();
+    mailer.send(&email("Second")).unwrap();
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+
+    assert_eq!(files.len(), 2);
+    assert!(files.iter().all(|path| path.extension().is_some_and(|ext| ext == "eml")));
+
+    let first = fs::read_to_string(&files[0]).unwrap();
+    assert!(first.starts_with("To: candidate@example.com\nSubject: First\n\n"));
+    assert!(first.contains("reset-password?token=abc"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_outbox_mailer_creates_missing_directory() {
+    let dir = outbox_dir().join("nested");
+    let mailer = OutboxMailer::new(&dir);
+
+    mailer.send(&email("Hello")).unwrap();
+
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+}
+
+#[tokio::test]
+async fn test_deliver_sends_through_the_mailer() {
+    let dir = outbox_dir();
+    let mailer: Arc<dyn Mailer> = Arc::new(OutboxMailer::new(&dir));
+
+    deliver(mailer, email("Async")).await.unwrap();
+
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_only_development_runs_without_an_outbox() {
+    std::env::remove_var("MAIL_OUTBOX_DIR");
+    assert!(mailer_from_env(false).is_none());
+    assert!(mailer_from_env(true).is_some());
+
+    std::env::set_var("MAIL_OUTBOX_DIR", outbox_dir());
+    assert!(mailer_from_env(false).is_some());
+    std::env::remove_var("MAIL_OUTBOX_DIR");
+}
@@ -0,0 +1,111 @@
+use chrono::Utc;
+use recruitment_backend::migrations::{
+    self, checksum, AppliedMigration, Migration, MigrationError, MIGRATIONS,
+};
+
+const KNOWN: &[Migration] = &[
+    Migration { version: 1, name: "first", up: "CREATE TABLE a (id INT);", down: "DROP TABLE a;" },
+    Migration { version: 2, name: "second", up: "CREATE TABLE b (id INT);", down: "DROP TABLE b;" },
+    Migration { version: 3, name: "third", up: "CREATE TABLE c (id INT);", down: "DROP TABLE c;" },
+];
+
+fn applied(migration: &Migration) -> AppliedMigration {
+    AppliedMigration {
+        version: migration.version,
+        name: migration.name.to_string(),
+        checksum: checksum(migration.up),
+        applied_at: Utc::now(),
+    }
+}
+
+fn versions(migrations: &[&Migration]) -> Vec<i64> {
+    migrations.iter().map(|migration| migration.version).collect()
+}
+
+#[test]
+fn test_embedded_migrations_are_numbered_in_order() {
+    assert!(!MIGRATIONS.is_empty());
+    assert_eq!(MIGRATIONS[0].version, 1);
+
+    for pair in MIGRATIONS.windows(2) {
+        assert_eq!(pair[1].version, pair[0].version + 1, "{} follows {}", pair[1].name, pair[0].name);
+    }
+
+    for migration in MIGRATIONS {
+        assert!(!migration.up.trim().is_empty(), "{} has no up script", migration.name);
+        assert!(!migration.down.trim().is_empty(), "{} has no down script", migration.name);
+    }
+}
+
+#[test]
+fn test_migrations_never_seed_data() {
+    for migration in MIGRATIONS {
+        assert!(!migration.up.contains("techsolutions.com"), "{} inserts demo data", migration.name);
+    }
+}
+
+#[test]
+fn test_checksum_is_sha256_hex() {
+    assert_eq!(
+        checksum(""),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_ne!(checksum(KNOWN[0].up), checksum(KNOWN[1].up));
+}
+
+#[test]
+fn test_pending_skips_applied_and_respects_target() {
+    let done = vec![applied(&KNOWN[0])];
+
+    assert_eq!(versions(&migrations::pending(KNOWN, &done, None).unwrap()), vec![2, 3]);
+    assert_eq!(versions(&migrations::pending(KNOWN, &done, Some(2)).unwrap()), vec![2]);
+    assert!(migrations::pending(KNOWN, &done, Some(1)).unwrap().is_empty());
+    assert!(matches!(
+        migrations::pending(KNOWN, &done, Some(7)),
+        Err(MigrationError::NoSuchVersion(7))
+    ));
+}
+
+#[test]
+fn test_edited_migrations_are_refused() {
+    let mut done = vec![applied(&KNOWN[0])];
+    done[0].checksum = checksum("CREATE TABLE a (id BIGINT);");
+
+    assert!(matches!(
+        migrations::pending(KNOWN, &done, None),
+        Err(MigrationError::ChecksumMismatch { version: 1, .. })
+    ));
+}
+
+#[test]
+fn test_migrations_from_a_newer_build_are_refused() {
+    let newer = Migration { version: 4, name: "fourth", up: "SELECT 1;", down: "SELECT 1;" };
+    let done: Vec<AppliedMigration> = KNOWN.iter().chain([&newer]).map(applied).collect();
+
+    assert!(matches!(
+        migrations::verify(KNOWN, &done),
+        Err(MigrationError::Unknown { version: 4, .. })
+    ));
+}
+
+#[test]
+fn test_revert_takes_the_latest_first() {
+    let done: Vec<AppliedMigration> = KNOWN.iter().map(applied).collect();
+
+    assert_eq!(versions(&migrations::to_revert(KNOWN, &done, 1).unwrap()), vec![3]);
+    assert_eq!(versions(&migrations::to_revert(KNOWN, &done, 2).unwrap()), vec![3, 2]);
+    assert_eq!(versions(&migrations::to_revert(KNOWN, &done, 10).unwrap()), vec![3, 2, 1]);
+    assert!(migrations::to_revert(KNOWN, &[], 1).unwrap().is_empty());
+}
+
+#[test]
+fn test_status_lists_every_migration() {
+    let done = vec![applied(&KNOWN[0]), applied(&KNOWN[1])];
+    let status = migrations::status(KNOWN, &done);
+
+    assert_eq!(status.len(), 3);
+    assert!(status[0].applied_at.is_some());
+    assert!(status[1].applied_at.is_some());
+    assert_eq!(status[2].name, "third");
+    assert!(status[2].applied_at.is_none());
+}
@@ -0,0 +1,63 @@
+use chrono::{Duration, NaiveDate, Utc};
+use recruitment_backend::models::{Offer, OfferStatus};
+use uuid::Uuid;
+
+fn offer(status: OfferStatus, expires_in: Duration) -> Offer {
+    Offer {
+        id: Uuid::new_v4(),
+        application_id: Uuid::new_v4(),
+        salary: 85_000,
+        currency: "EUR".to_string(),
+        start_date: NaiveDate::from_ymd_opt(2030, 1, 1).unwrap(),
+        expires_at: Utc::now() + expires_in,
+        status,
+        notes: None,
+        version: 1,
+        created_by: None,
+        sent_at: None,
+        responded_at: None,
+        created_at: Utc::now(),
+        updated_at: None,
+    }
+}
+
+#[test]
+fn test_offer_status_lifecycle() {
+    assert!(OfferStatus::Draft.can_transition_to(OfferStatus::Sent));
+    assert!(OfferStatus::Draft.can_transition_to(OfferStatus::Rescinded));
+
+    for next in [OfferStatus::Accepted, OfferStatus::Declined, OfferStatus::Expired, OfferStatus::Rescinded] {
+        assert!(OfferStatus::Sent.can_transition_to(next), "sent -> {} should be allowed", next);
+    }
+}
+
+#[test]
+fn test_offer_status_drafts_cannot_be_answered() {
+    // Candidates only answer offers they were sent, and drafts never expire
+    for next in [OfferStatus::Accepted, OfferStatus::Declined, OfferStatus::Expired] {
+        assert!(!OfferStatus::Draft.can_transition_to(next), "draft -> {} should be refused", next);
+    }
+}
+
+#[test]
+fn test_offer_status_closed_offers_are_final() {
+    let closed = [OfferStatus::Accepted, OfferStatus::Declined, OfferStatus::Expired, OfferStatus::Rescinded];
+
+    for status in closed {
+        assert!(!status.is_open());
+        assert!(status.allowed_transitions().is_empty());
+    }
+
+    assert!(OfferStatus::Draft.is_open());
+    assert!(OfferStatus::Sent.is_open());
+}
+
+#[test]
+fn test_only_sent_offers_past_their_deadline_are_overdue() {
+    let now = Utc::now();
+
+    assert!(offer(OfferStatus::Sent, Duration::minutes(-1)).is_overdue(now));
+    assert!(!offer(OfferStatus::Sent, Duration::days(1)).is_overdue(now));
+    assert!(!offer(OfferStatus::Draft, Duration::minutes(-1)).is_overdue(now));
+    assert!(!offer(OfferStatus::Accepted, Duration::minutes(-1)).is_overdue(now));
+}
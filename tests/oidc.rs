@@ -0,0 +1,120 @@
+mod mock_idp;
+
+use jsonwebtoken::jwk::JwkSet;
+use recruitment_backend::oidc::{self, ProviderMetadata};
+use serde_json::json;
+
+fn provider_jwks() -> JwkSet {
+    serde_json::from_value(mock_idp::jwks()).unwrap()
+}
+
+fn id_token(issuer: &str, audience: &str, nonce: &str) -> String {
+    let now = chrono::Utc::now().timestamp();
+    mock_idp::sign(&json!({
+        "iss": issuer,
+        "aud": audience,
+        "sub": "user-1",
+        "email": "jane@acme.example",
+        "email_verified": true,
+        "nonce": nonce,
+        "iat": now,
+        "exp": now + 300
+    }))
+}
+
+#[test]
+fn test_pkce_challenge_matches_rfc_7636() {
+    assert_eq!(
+        oidc::pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+        "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+    );
+}
+
+#[test]
+fn test_authorization_url_carries_state_nonce_and_challenge() {
+    let metadata = ProviderMetadata {
+        issuer: "https://idp.example".to_string(),
+        authorization_endpoint: "https://idp.example/authorize".to_string(),
+        token_endpoint: "https://idp.example/token".to_string(),
+        jwks_uri: "https://idp.example/jwks".to_string(),
+    };
+
+    let url = oidc::authorization_url(&metadata, "app", "http://localhost:3000/cb", "s1", "n1", "v1").unwrap();
+    let url = reqwest::Url::parse(&url).unwrap();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+
+    assert_eq!(url.path(), "/authorize");
+    assert_eq!(param("response_type").as_deref(), Some("code"));
+    assert_eq!(param("client_id").as_deref(), Some("app"));
+    assert_eq!(param("redirect_uri").as_deref(), Some("http://localhost:3000/cb"));
+    assert_eq!(param("state").as_deref(), Some("s1"));
+    assert_eq!(param("nonce").as_deref(), Some("n1"));
+    assert_eq!(param("code_challenge"), Some(oidc::pkce_challenge("v1")));
+    assert_eq!(param("code_challenge_method").as_deref(), Some("S256"));
+}
+
+#[test]
+fn test_verify_id_token_accepts_a_valid_token() {
+    let token = id_token("https://idp.example", "app", "n1");
+
+    let claims = oidc::verify_id_token(&token, &provider_jwks(), "https://idp.example", "app", "n1").unwrap();
+    assert_eq!(claims.email.as_deref(), Some("jane@acme.example"));
+    assert!(claims.is_email_verified());
+}
+
+#[test]
+fn test_verify_id_token_rejects_wrong_nonce_audience_or_issuer() {
+    let jwks = provider_jwks();
+
+    let token = id_token("https://idp.example", "app", "n1");
+    assert!(oidc::verify_id_token(&token, &jwks, "https://idp.example", "app", "other").is_err());
+
+    let token = id_token("https://idp.example", "another-app", "n1");
+    assert!(oidc::verify_id_token(&token, &jwks, "https://idp.example", "app", "n1").is_err());
+
+    let token = id_token("https://evil.example", "app", "n1");
+    assert!(oidc::verify_id_token(&token, &jwks, "https://idp.example", "app", "n1").is_err());
+}
+
+#[test]
+fn test_verify_id_token_rejects_symmetric_signatures() {
+    let now = chrono::Utc::now().timestamp();
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &json!({ "iss": "https://idp.example", "aud": "app", "sub": "x", "nonce": "n1", "exp": now + 300 }),
+        &jsonwebtoken::EncodingKey::from_secret(b"guessable"),
+    )
+    .unwrap();
+
+    assert!(oidc::verify_id_token(&token, &provider_jwks(), "https://idp.example", "app", "n1").is_err());
+}
+
+#[tokio::test]
+async fn test_code_exchange_against_mock_provider() {
+    let idp = mock_idp::MockIdp::start().await;
+    let metadata = oidc::discover(&idp.issuer).await.unwrap();
+    assert_eq!(metadata.token_endpoint, format!("{}/token", idp.issuer));
+
+    let verifier = oidc::random_token();
+    let code = idp.authorize(&oidc::pkce_challenge(&verifier), "n1", "jane@acme.example", true);
+
+    // The wrong verifier is refused, and the code cannot be replayed afterwards
+    let wrong = oidc::exchange_code(&metadata, mock_idp::CLIENT_ID, mock_idp::CLIENT_SECRET, "http://cb", &code, "wrong").await;
+    assert!(wrong.is_err());
+
+    let code = idp.authorize(&oidc::pkce_challenge(&verifier), "n1", "jane@acme.example", true);
+    let token = oidc::exchange_code(&metadata, mock_idp::CLIENT_ID, mock_idp::CLIENT_SECRET, "http://cb", &code, &verifier)
+        .await
+        .unwrap();
+
+    let jwks = oidc::fetch_jwks(&metadata).await.unwrap();
+    let claims = oidc::verify_id_token(&token, &jwks, &idp.issuer, mock_idp::CLIENT_ID, "n1").unwrap();
+    assert_eq!(claims.email.as_deref(), Some("jane@acme.example"));
+
+    let replay = oidc::exchange_code(&metadata, mock_idp::CLIENT_ID, mock_idp::CLIENT_SECRET, "http://cb", &code, &verifier).await;
+    assert!(replay.is_err());
+}
@@ -0,0 +1,78 @@
+use recruitment_backend::passwords::{self, parse_character_classes, Argon2Config, CharacterClass, PasswordPolicy};
+
+// Cheap parameters so the suite stays fast
+const TEST_CONFIG: Argon2Config = Argon2Config { memory_kib: 1024, iterations: 1, parallelism: 1 };
+
+#[test]
+fn test_argon2_hashes_verify_and_use_argon2id() {
+    let hash = passwords::hash_password(&TEST_CONFIG, "Correct-Horse-1").unwrap();
+
+    assert!(hash.starts_with("$argon2id$"));
+    assert!(passwords::verify_password("Correct-Horse-1", &hash).unwrap());
+    assert!(!passwords::verify_password("Wrong-Horse-1", &hash).unwrap());
+    assert!(!passwords::needs_rehash(&TEST_CONFIG, &hash));
+}
+
+#[test]
+fn test_long_passwords_are_not_truncated() {
+    let password = "a".repeat(72);
+    let hash = passwords::hash_password(&TEST_CONFIG, &password).unwrap();
+
+    assert!(!passwords::verify_password(&format!("{}b", password), &hash).unwrap());
+}
+
+#[test]
+fn test_legacy_bcrypt_hashes_verify_and_need_rehash() {
+    let hash = bcrypt::hash("Legacy-Password-1", 4).unwrap();
+
+    assert!(passwords::verify_password("Legacy-Password-1", &hash).unwrap());
+    assert!(!passwords::verify_password("Other-Password-1", &hash).unwrap());
+    assert!(passwords::needs_rehash(&TEST_CONFIG, &hash));
+}
+
+#[test]
+fn test_changed_parameters_need_rehash() {
+    let hash = passwords::hash_password(&TEST_CONFIG, "Correct-Horse-1").unwrap();
+    let stronger = Argon2Config { iterations: 2, ..TEST_CONFIG };
+
+    assert!(passwords::needs_rehash(&stronger, &hash));
+    // Still verifies with the parameters stored in the hash
+    assert!(passwords::verify_password("Correct-Horse-1", &hash).unwrap());
+}
+
+#[test]
+fn test_default_policy_keeps_the_previous_rules() {
+    let policy = PasswordPolicy::default();
+
+    assert!(policy.validate("Str0ng-Pass").is_ok());
+    assert!(policy.validate("Sh0rt-").is_err());
+    assert!(policy.validate("no-uppercase-1").is_err());
+    assert!(policy.validate("No-Digits-Here").is_err());
+    assert!(policy.validate(&format!("Aa1-{}", "x".repeat(200))).is_err());
+}
+
+#[test]
+fn test_denylisted_passwords_are_refused_without_case() {
+    let policy = PasswordPolicy::default().with_denylist("# common passwords\nPassword123!\n\nQwerty-123\n");
+
+    assert_eq!(policy.denylist_len(), 2);
+    assert!(policy.validate("password123!").is_err());
+    assert!(policy.validate("QWERTY-123").is_err());
+    assert!(policy.validate("Unusual-Pass-9").is_ok());
+}
+
+#[test]
+fn test_required_classes_are_configurable() {
+    assert_eq!(
+        parse_character_classes("lowercase, digit"),
+        Some(vec![CharacterClass::Lowercase, CharacterClass::Digit])
+    );
+    assert_eq!(parse_character_classes(""), Some(vec![]));
+    assert_eq!(parse_character_classes("lowercase,emoji"), None);
+
+    let mut policy = PasswordPolicy::default();
+    policy.min_length = 12;
+    policy.required_classes = vec![];
+    assert!(policy.validate("correct horse battery").is_ok());
+    assert!(policy.validate("too short").is_err());
+}
@@ -0,0 +1,92 @@
+use recruitment_backend::permissions::{scope_permission, Permission, Role, TeamRole, API_KEY_SCOPES};
+
+#[test]
+fn test_admin_has_every_permission() {
+    for permission in Permission::ALL {
+        assert!(Role::Admin.has_permission(*permission));
+    }
+}
+
+#[test]
+fn test_workers_cannot_manage_agency_records() {
+    assert!(!Role::Worker.has_permission(Permission::ManageClients));
+    assert!(!Role::Worker.has_permission(Permission::ManageWorkers));
+    assert!(!Role::Worker.has_permission(Permission::ManageApplications));
+    assert!(Role::Worker.has_permission(Permission::ApplyToJobs));
+    assert!(Role::Worker.has_permission(Permission::RespondToOffers));
+}
+
+#[test]
+fn test_clients_manage_their_pipeline_but_not_offers() {
+    assert!(Role::Client.has_permission(Permission::ManageJobs));
+    assert!(Role::Client.has_permission(Permission::ManageApplications));
+    assert!(!Role::Client.has_permission(Permission::ManageOffers));
+    assert!(!Role::Client.has_permission(Permission::ApplyToJobs));
+}
+
+#[test]
+fn test_unknown_roles_are_rejected() {
+    assert_eq!(Role::parse("client"), Some(Role::Client));
+    assert_eq!(Role::parse("superuser"), None);
+}
+
+#[test]
+fn test_unverified_accounts_keep_read_access() {
+    assert!(!Permission::ViewJobs.requires_verified_email());
+    assert!(!Permission::ViewOffers.requires_verified_email());
+    assert!(Permission::ApplyToJobs.requires_verified_email());
+    assert!(Permission::RespondToOffers.requires_verified_email());
+    assert!(Permission::ManageJobs.requires_verified_email());
+}
+
+#[test]
+fn test_api_key_scopes_stay_within_the_client_role() {
+    for (scope, permission) in API_KEY_SCOPES {
+        assert!(Role::Client.has_permission(*permission), "{} grants more than a client has", scope);
+        assert_ne!(*permission, Permission::ManageApiKeys);
+        assert!(TeamRole::Recruiter.has_permission(*permission), "{} grants more than a recruiter has", scope);
+    }
+}
+
+#[test]
+fn test_api_key_scope_names() {
+    assert_eq!(scope_permission("jobs:write"), Some(Permission::ManageJobs));
+    assert_eq!(scope_permission("applications:read"), Some(Permission::ViewApplications));
+    assert_eq!(scope_permission("offers:write"), None);
+}
+
+#[test]
+fn test_team_roles_never_exceed_the_client_role() {
+    for team_role in [TeamRole::Owner, TeamRole::Recruiter, TeamRole::HiringManager, TeamRole::Viewer] {
+        for permission in team_role.permissions() {
+            assert!(Role::Client.has_permission(*permission), "{} may {:?}", team_role.as_str(), permission);
+        }
+        assert_eq!(TeamRole::parse(team_role.as_str()), Some(team_role));
+    }
+}
+
+#[test]
+fn test_only_owners_manage_the_team() {
+    assert!(TeamRole::Owner.has_permission(Permission::ManageTeam));
+    assert!(TeamRole::Owner.has_permission(Permission::ManageApiKeys));
+    assert!(!TeamRole::Recruiter.has_permission(Permission::ManageTeam));
+    assert!(!TeamRole::HiringManager.has_permission(Permission::ManageTeam));
+}
+
+#[test]
+fn test_hiring_managers_and_viewers_cannot_change_jobs() {
+    assert!(!TeamRole::HiringManager.has_permission(Permission::ManageJobs));
+    assert!(TeamRole::HiringManager.has_permission(Permission::ManageApplications));
+    assert!(!TeamRole::HiringManager.has_permission(Permission::ViewBilling));
+    assert!(!TeamRole::Viewer.has_permission(Permission::ManageApplications));
+    assert!(TeamRole::Viewer.has_permission(Permission::ViewApplications));
+}
+
+#[test]
+fn test_impersonation_cannot_touch_credentials_or_access() {
+    assert!(!Permission::ManageApiKeys.allowed_while_impersonating());
+    assert!(!Permission::ManageTeam.allowed_while_impersonating());
+    assert!(!Permission::ManageUsers.allowed_while_impersonating());
+    assert!(Permission::ViewJobs.allowed_while_impersonating());
+    assert!(Permission::ManageApplications.allowed_while_impersonating());
+}
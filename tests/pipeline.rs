@@ -0,0 +1,202 @@
+use chrono::Utc;
+use recruitment_backend::handlers::pipeline::{
+    group_board, place_card, remapped_stages, stage_status, status_for_open_stage, validate_pipeline,
+    DEFAULT_PIPELINE_STAGES,
+};
+use recruitment_backend::models::{
+    ApplicationStatus, BoardCard, BoardWorkerSummary, PipelineStage, PipelineStageInput,
+};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+fn stages(layout: &[(&str, bool, bool)]) -> Vec<PipelineStage> {
+    let job_id = Uuid::new_v4();
+    layout
+        .iter()
+        .enumerate()
+        .map(|(position, (name, is_terminal, is_success))| PipelineStage {
+            id: Uuid::new_v4(),
+            job_id,
+            name: name.to_string(),
+            position: position as i32,
+            is_terminal: *is_terminal,
+            is_success: *is_success,
+            created_at: Utc::now(),
+            updated_at: None,
+        })
+        .collect()
+}
+
+fn input(name: &str, is_terminal: bool, is_success: bool) -> PipelineStageInput {
+    PipelineStageInput {
+        id: None,
+        name: name.to_string(),
+        is_terminal: Some(is_terminal),
+        is_success: Some(is_success),
+    }
+}
+
+fn card(position: i32) -> BoardCard {
+    BoardCard {
+        application_id: Uuid::new_v4(),
+        status: ApplicationStatus::Pending,
+        position,
+        days_in_stage: 0,
+        applied_at: Utc::now(),
+        worker: BoardWorkerSummary {
+            id: Uuid::new_v4(),
+            name: "Candidate".to_string(),
+            top_matching_skills: Vec::new(),
+            match_score: 0.0,
+        },
+    }
+}
+
+#[test]
+fn test_default_stages_follow_the_status_machine() {
+    let stages = stages(&DEFAULT_PIPELINE_STAGES);
+    let statuses: Vec<_> = stages.iter().map(|stage| stage_status(&stages, stage.id).unwrap()).collect();
+
+    assert_eq!(
+        statuses,
+        [
+            ApplicationStatus::Pending,
+            ApplicationStatus::Reviewing,
+            ApplicationStatus::Interview,
+            ApplicationStatus::Offer,
+            ApplicationStatus::Hired,
+            ApplicationStatus::Rejected,
+        ]
+    );
+}
+
+#[test]
+fn test_open_stage_statuses_for_custom_pipelines() {
+    use ApplicationStatus::*;
+
+    let layouts: [(usize, &[ApplicationStatus]); 4] = [
+        (1, &[Pending]),
+        (2, &[Pending, Offer]),
+        (3, &[Pending, Reviewing, Offer]),
+        (6, &[Pending, Reviewing, Reviewing, Reviewing, Interview, Offer]),
+    ];
+
+    for (open_stages, expected) in layouts {
+        let statuses: Vec<_> = (0..open_stages).map(|index| status_for_open_stage(index, open_stages)).collect();
+        assert_eq!(statuses, expected, "{} open stages", open_stages);
+    }
+}
+
+#[test]
+fn test_terminal_stages_are_ignored_when_ranking_open_stages() {
+    // A terminal stage placed between open ones
+    let stages = stages(&[
+        ("New", false, false),
+        ("Withdrawn early", true, false),
+        ("Final round", false, false),
+        ("Placed", true, true),
+    ]);
+
+    assert_eq!(stage_status(&stages, stages[2].id), Some(ApplicationStatus::Offer));
+    assert_eq!(stage_status(&stages, stages[1].id), Some(ApplicationStatus::Rejected));
+    assert_eq!(stage_status(&stages, stages[3].id), Some(ApplicationStatus::Hired));
+    assert_eq!(stage_status(&stages, Uuid::new_v4()), None);
+}
+
+#[test]
+fn test_pipeline_validation() {
+    assert!(validate_pipeline(&[input("Applied", false, false), input("Hired", true, true)]).is_ok());
+
+    assert!(validate_pipeline(&[]).is_err());
+    assert!(validate_pipeline(&[input("  ", false, false)]).is_err());
+    assert!(validate_pipeline(&[input("Applied", false, false), input("applied ", false, false)]).is_err());
+    assert!(validate_pipeline(&[input("Applied", false, false), input("Hired", false, true)]).is_err());
+    assert!(validate_pipeline(&[input("Hired", true, true), input("Applied", false, false)]).is_err());
+}
+
+fn kept(stage: &PipelineStage) -> PipelineStageInput {
+    PipelineStageInput {
+        id: Some(stage.id),
+        name: stage.name.clone(),
+        is_terminal: Some(stage.is_terminal),
+        is_success: Some(stage.is_success),
+    }
+}
+
+#[test]
+fn test_occupied_stages_may_be_renamed_but_not_remapped() {
+    let stages = stages(&DEFAULT_PIPELINE_STAGES);
+    let screening = stages[1].id;
+    let offer = stages[3].id;
+    let occupied: HashSet<Uuid> = [screening, offer].into_iter().collect();
+
+    let mut renamed: Vec<_> = stages.iter().map(kept).collect();
+    renamed[1].name = "Phone screen".to_string();
+    assert!(remapped_stages(&stages, &renamed, &occupied).is_empty());
+
+    // A new open stage after Offer turns Offer into Interview
+    let mut extended: Vec<_> = stages.iter().map(kept).collect();
+    extended.insert(4, input("Reference check", false, false));
+    assert_eq!(remapped_stages(&stages, &extended, &occupied), vec![offer]);
+
+    let mut swapped: Vec<_> = stages.iter().map(kept).collect();
+    swapped.swap(1, 2);
+    assert_eq!(remapped_stages(&stages, &swapped, &occupied), vec![screening]);
+
+    let mut closed: Vec<_> = stages.iter().map(kept).collect();
+    closed[3].is_terminal = Some(true);
+    assert_eq!(remapped_stages(&stages, &closed, &occupied), vec![offer]);
+}
+
+#[test]
+fn test_empty_stages_can_change_freely() {
+    let stages = stages(&DEFAULT_PIPELINE_STAGES);
+    let mut reversed: Vec<_> = stages[..4].iter().map(kept).collect();
+    reversed[1..].reverse();
+
+    assert!(remapped_stages(&stages, &reversed, &HashSet::new()).is_empty());
+}
+
+#[test]
+fn test_place_card_renumbers_the_column() {
+    let [a, b, c, moved] = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+
+    assert_eq!(place_card(&[a, b, c], moved, 1), [a, moved, b, c]);
+    assert_eq!(place_card(&[a, b, c], moved, 0), [moved, a, b, c]);
+    // Past the end lands last
+    assert_eq!(place_card(&[a, b, c], moved, 10), [a, b, c, moved]);
+    // Reordering within the column
+    assert_eq!(place_card(&[a, b, c], a, 2), [b, c, a]);
+    assert_eq!(place_card(&[], moved, 3), [moved]);
+}
+
+#[test]
+fn test_board_groups_cards_by_stage() {
+    let job_id = Uuid::new_v4();
+    let stages = stages(&DEFAULT_PIPELINE_STAGES);
+    let (applied, interview) = (stages[0].id, stages[2].id);
+
+    let first = card(0);
+    let second = card(1);
+    let ids = [first.application_id, second.application_id];
+    let cards = vec![
+        (Some(interview), first),
+        (Some(interview), second),
+        (Some(applied), card(0)),
+        // Unknown and missing stages fall back to the entry column
+        (Some(Uuid::new_v4()), card(1)),
+        (None, card(2)),
+    ];
+
+    let board = group_board(job_id, stages, cards);
+
+    assert_eq!(board.job_id, job_id);
+    assert_eq!(board.total, 5);
+    assert_eq!(board.columns.len(), DEFAULT_PIPELINE_STAGES.len());
+
+    let counts: Vec<usize> = board.columns.iter().map(|column| column.count).collect();
+    assert_eq!(counts, [3, 0, 2, 0, 0, 0]);
+
+    let interview_ids: Vec<Uuid> = board.columns[2].cards.iter().map(|card| card.application_id).collect();
+    assert_eq!(interview_ids, ids);
+}
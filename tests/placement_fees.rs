@@ -0,0 +1,63 @@
+use chrono::Utc;
+use recruitment_backend::models::{FeeAgreement, FeeType};
+use recruitment_backend::utils::{is_valid_salary, MAX_SALARY};
+use uuid::Uuid;
+
+fn agreement(fee_type: FeeType, percentage_bps: Option<i32>, flat_fee: Option<i64>) -> FeeAgreement {
+    FeeAgreement {
+        id: Uuid::new_v4(),
+        client_id: Uuid::new_v4(),
+        fee_type,
+        percentage_bps,
+        flat_fee,
+        currency: "USD".to_string(),
+        payment_terms_days: 30,
+        created_at: Utc::now(),
+        updated_at: None,
+    }
+}
+
+#[test]
+fn test_percentage_fee_of_first_year_salary() {
+    let twenty_percent = agreement(FeeType::Percentage, Some(2000), None);
+    assert_eq!(twenty_percent.fee_for_salary(100_000).unwrap(), 20_000);
+}
+
+#[test]
+fn test_percentage_fee_rounds_half_up() {
+    // 12.5% of 99,999 = 12,499.875
+    let fee = agreement(FeeType::Percentage, Some(1250), None);
+    assert_eq!(fee.fee_for_salary(99_999).unwrap(), 12_500);
+}
+
+#[test]
+fn test_flat_fee_ignores_salary() {
+    let flat = agreement(FeeType::Flat, None, Some(8_000));
+    assert_eq!(flat.fee_for_salary(50_000).unwrap(), 8_000);
+    assert_eq!(flat.fee_for_salary(250_000).unwrap(), 8_000);
+}
+
+#[test]
+fn test_percentage_fee_overflow_is_an_error() {
+    let full = agreement(FeeType::Percentage, Some(10_000), None);
+    assert!(full.fee_for_salary(i64::MAX / 100).is_err());
+    assert!(full.fee_for_salary(i64::MAX).is_err());
+
+    // The largest salary accepted still has a fee
+    assert_eq!(full.fee_for_salary(MAX_SALARY).unwrap(), MAX_SALARY);
+}
+
+#[test]
+fn test_fee_needs_the_agreed_amount() {
+    assert!(agreement(FeeType::Percentage, None, None).fee_for_salary(100_000).is_err());
+    assert!(agreement(FeeType::Flat, None, None).fee_for_salary(100_000).is_err());
+}
+
+#[test]
+fn test_salary_bounds() {
+    assert!(is_valid_salary(1));
+    assert!(is_valid_salary(MAX_SALARY));
+    assert!(!is_valid_salary(0));
+    assert!(!is_valid_salary(-5));
+    assert!(!is_valid_salary(MAX_SALARY + 1));
+}
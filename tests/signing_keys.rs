@@ -0,0 +1,61 @@
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, jwk::JwkSet, Algorithm, DecodingKey, Header, Validation};
+use recruitment_backend::signing_keys::{self, KeyRing, SigningKey};
+use serde_json::{json, Value};
+
+fn sign_with(keyring: &KeyRing) -> String {
+    let (kid, key) = keyring.signing_key(Utc::now()).expect("No signing key");
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(kid);
+    encode(&header, &json!({ "sub": "user-1", "exp": Utc::now().timestamp() + 60 }), &key).unwrap()
+}
+
+#[test]
+fn test_tokens_verify_against_the_published_jwks() {
+    let (key, _) = SigningKey::generate(Utc::now() - Duration::minutes(1)).unwrap();
+    let keyring = KeyRing::new(vec![key]);
+    let token = sign_with(&keyring);
+
+    // What another service does with /.well-known/jwks.json
+    let jwks: JwkSet = serde_json::from_value(keyring.jwks()).unwrap();
+    let kid = jsonwebtoken::decode_header(&token).unwrap().kid.unwrap();
+    let key = DecodingKey::from_jwk(jwks.find(&kid).unwrap()).unwrap();
+
+    let claims = decode::<Value>(&token, &key, &Validation::new(Algorithm::EdDSA)).unwrap().claims;
+    assert_eq!(claims["sub"], "user-1");
+}
+
+#[test]
+fn test_pending_keys_are_published_but_do_not_sign() {
+    let now = Utc::now();
+    let (current, _) = SigningKey::generate(now - Duration::days(1)).unwrap();
+    let (next, _) = SigningKey::generate(now + Duration::minutes(10)).unwrap();
+    let (current_kid, next_kid) = (current.kid.clone(), next.kid.clone());
+    let keyring = KeyRing::new(vec![next, current]);
+
+    assert_eq!(keyring.signing_key(now).unwrap().0, current_kid);
+    assert_eq!(keyring.signing_key(now + Duration::minutes(11)).unwrap().0, next_kid);
+    assert!(keyring.decoding_key(&next_kid).is_some());
+    assert_eq!(keyring.jwks()["keys"].as_array().unwrap().len(), 2);
+    assert!(keyring.decoding_key("unknown").is_none());
+}
+
+#[test]
+fn test_private_keys_only_open_with_the_same_secret_and_kid() {
+    let secret = "a-long-enough-secret-for-the-test-suite";
+    let (key, pkcs8) = SigningKey::generate(Utc::now()).unwrap();
+    let sealed = signing_keys::seal_private_key(secret, &key.kid, &pkcs8);
+
+    assert_ne!(sealed, pkcs8);
+    assert_eq!(signing_keys::open_private_key(secret, &key.kid, &sealed), Some(pkcs8));
+    assert_eq!(signing_keys::open_private_key("another-long-enough-secret-for-tests", &key.kid, &sealed), None);
+    assert_eq!(signing_keys::open_private_key(secret, "other-kid", &sealed), None);
+}
+
+#[test]
+fn test_placeholder_and_short_secrets_are_weak() {
+    assert!(signing_keys::is_weak_secret(signing_keys::DEVELOPMENT_JWT_SECRET));
+    assert!(signing_keys::is_weak_secret("your-super-secret-jwt-key-here-change-this-in-production"));
+    assert!(signing_keys::is_weak_secret("short"));
+    assert!(!signing_keys::is_weak_secret("9f2c4e1ab7d35e08c6a1f4b29d7e0c3a"));
+}
@@ -0,0 +1,135 @@
+//! A software WebAuthn authenticator for tests: one P-256 passkey, answering
+//! registration and login ceremonies the way a browser would report them.
+
+// Shared by several test crates, each using part of it
+#![allow(dead_code)]
+
+use ciborium::value::Value;
+use data_encoding::BASE64URL_NOPAD;
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+use serde_json::{json, Value as Json};
+use sha2::{Digest, Sha256};
+
+const FLAGS_REGISTRATION: u8 = 0x01 | 0x04 | 0x40; // UP | UV | AT
+const FLAGS_ASSERTION: u8 = 0x01 | 0x04; // UP | UV
+
+pub struct SoftAuthenticator {
+    key_pair: EcdsaKeyPair,
+    rng: SystemRandom,
+    credential_id: Vec<u8>,
+    rp_id: String,
+    pub origin: String,
+    pub sign_count: u32,
+    user_handle: Option<String>,
+}
+
+impl SoftAuthenticator {
+    pub fn new(rp_id: &str, origin: &str) -> Self {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap();
+
+        Self {
+            key_pair,
+            rng,
+            credential_id: uuid::Uuid::new_v4().as_bytes().to_vec(),
+            rp_id: rp_id.to_string(),
+            origin: origin.to_string(),
+            sign_count: 0,
+            user_handle: None,
+        }
+    }
+
+    pub fn credential_id(&self) -> String {
+        BASE64URL_NOPAD.encode(&self.credential_id)
+    }
+
+    fn client_data(&self, ceremony: &str, challenge: &str) -> Vec<u8> {
+        json!({
+            "type": ceremony,
+            "challenge": challenge,
+            "origin": self.origin,
+            "crossOrigin": false
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    fn authenticator_data(&self, flags: u8) -> Vec<u8> {
+        let mut data = Sha256::digest(self.rp_id.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend_from_slice(&self.sign_count.to_be_bytes());
+        data
+    }
+
+    fn cose_key(&self) -> Vec<u8> {
+        let point = self.key_pair.public_key().as_ref();
+        let key = Value::Map(vec![
+            (Value::Integer(1.into()), Value::Integer(2.into())),
+            (Value::Integer(3.into()), Value::Integer((-7).into())),
+            (Value::Integer((-1).into()), Value::Integer(1.into())),
+            (Value::Integer((-2).into()), Value::Bytes(point[1..33].to_vec())),
+            (Value::Integer((-3).into()), Value::Bytes(point[33..65].to_vec())),
+        ]);
+
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(&key, &mut bytes).unwrap();
+        bytes
+    }
+
+    /// Answers `creation_options`, as `PublicKeyCredential.toJSON()`.
+    pub fn create(&mut self, options: &Json) -> Json {
+        self.user_handle = options["user"]["id"].as_str().map(str::to_string);
+        let challenge = options["challenge"].as_str().unwrap();
+
+        let mut auth_data = self.authenticator_data(FLAGS_REGISTRATION);
+        auth_data.extend_from_slice(&[0u8; 16]); // AAGUID, none for a software key
+        auth_data.extend_from_slice(&(self.credential_id.len() as u16).to_be_bytes());
+        auth_data.extend_from_slice(&self.credential_id);
+        auth_data.extend_from_slice(&self.cose_key());
+
+        let attestation = Value::Map(vec![
+            (Value::Text("fmt".into()), Value::Text("none".into())),
+            (Value::Text("attStmt".into()), Value::Map(vec![])),
+            (Value::Text("authData".into()), Value::Bytes(auth_data)),
+        ]);
+        let mut attestation_object = Vec::new();
+        ciborium::ser::into_writer(&attestation, &mut attestation_object).unwrap();
+
+        json!({
+            "id": self.credential_id(),
+            "rawId": self.credential_id(),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": BASE64URL_NOPAD.encode(&self.client_data("webauthn.create", challenge)),
+                "attestationObject": BASE64URL_NOPAD.encode(&attestation_object)
+            }
+        })
+    }
+
+    /// Answers `request_options` with a signed assertion, bumping the counter.
+    pub fn get(&mut self, options: &Json) -> Json {
+        self.sign_count += 1;
+        let challenge = options["challenge"].as_str().unwrap();
+
+        let client_data = self.client_data("webauthn.get", challenge);
+        let auth_data = self.authenticator_data(FLAGS_ASSERTION);
+
+        let mut signed = auth_data.clone();
+        signed.extend_from_slice(&Sha256::digest(&client_data));
+        let signature = self.key_pair.sign(&self.rng, &signed).unwrap();
+
+        json!({
+            "id": self.credential_id(),
+            "rawId": self.credential_id(),
+            "type": "public-key",
+            "response": {
+                "clientDataJSON": BASE64URL_NOPAD.encode(&client_data),
+                "authenticatorData": BASE64URL_NOPAD.encode(&auth_data),
+                "signature": BASE64URL_NOPAD.encode(signature.as_ref()),
+                "userHandle": self.user_handle
+            }
+        })
+    }
+}
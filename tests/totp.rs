@@ -0,0 +1,63 @@
+use recruitment_backend::totp;
+
+// ASCII "12345678901234567890", the RFC 6238 SHA-1 test key
+const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+#[test]
+fn test_codes_match_rfc_6238_vectors() {
+    // Last six digits of the RFC's eight-digit values
+    assert_eq!(totp::code_at(RFC_SECRET, 59).as_deref(), Some("287082"));
+    assert_eq!(totp::code_at(RFC_SECRET, 1111111109).as_deref(), Some("081804"));
+    assert_eq!(totp::code_at(RFC_SECRET, 1111111111).as_deref(), Some("050471"));
+    assert_eq!(totp::code_at(RFC_SECRET, 1234567890).as_deref(), Some("005924"));
+    assert_eq!(totp::code_at(RFC_SECRET, 2000000000).as_deref(), Some("279037"));
+}
+
+#[test]
+fn test_verify_tolerates_one_step_of_drift() {
+    let now = 1_700_000_000;
+    let previous = totp::code_at(RFC_SECRET, now - 30).unwrap();
+    let next = totp::code_at(RFC_SECRET, now + 30).unwrap();
+    let too_old = totp::code_at(RFC_SECRET, now - 90).unwrap();
+
+    assert_eq!(totp::verify(RFC_SECRET, &previous, now, None), Some(totp::step_at(now) - 1));
+    assert_eq!(totp::verify(RFC_SECRET, &next, now, None), Some(totp::step_at(now) + 1));
+    assert_eq!(totp::verify(RFC_SECRET, &too_old, now, None), None);
+}
+
+#[test]
+fn test_verify_refuses_reused_codes() {
+    let now = 1_700_000_000;
+    let code = totp::code_at(RFC_SECRET, now).unwrap();
+
+    let step = totp::verify(RFC_SECRET, &code, now, None).expect("code is valid");
+    assert_eq!(totp::verify(RFC_SECRET, &code, now, Some(step)), None);
+}
+
+#[test]
+fn test_verify_rejects_malformed_codes() {
+    let now = 1_700_000_000;
+
+    assert_eq!(totp::verify(RFC_SECRET, "12345", now, None), None);
+    assert_eq!(totp::verify(RFC_SECRET, "abcdef", now, None), None);
+    assert_eq!(totp::verify("not base32!", "123456", now, None), None);
+}
+
+#[test]
+fn test_generated_secrets_are_usable() {
+    let secret = totp::generate_secret();
+
+    assert_eq!(secret.len(), 32);
+    assert!(totp::code_at(&secret, 0).is_some());
+    assert_ne!(secret, totp::generate_secret());
+}
+
+#[test]
+fn test_provisioning_uri_escapes_the_label() {
+    let uri = totp::provisioning_uri(RFC_SECRET, "jane doe@example.com", "Recruitment Agency");
+
+    assert_eq!(
+        uri,
+        "otpauth://totp/Recruitment%20Agency:jane%20doe@example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Recruitment%20Agency&algorithm=SHA1&digits=6&period=30"
+    );
+}
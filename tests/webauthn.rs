@@ -0,0 +1,130 @@
+mod soft_authenticator;
+
+use recruitment_backend::error::AppError;
+use recruitment_backend::webauthn::{
+    self, AssertionCredential, RegistrationCredential, RelyingParty, VerifiedCredential, COSE_ALG_ES256,
+};
+use soft_authenticator::SoftAuthenticator;
+use uuid::Uuid;
+
+const ORIGIN: &str = "https://agency.example.com";
+
+fn relying_party() -> RelyingParty {
+    RelyingParty::from_origin(ORIGIN, "Recruitment Agency").unwrap()
+}
+
+fn register(rp: &RelyingParty, authenticator: &mut SoftAuthenticator) -> VerifiedCredential {
+    let challenge = webauthn::new_challenge();
+    let options = webauthn::creation_options(rp, Uuid::new_v4(), "worker@example.com", &challenge, &[]);
+    let credential: RegistrationCredential = serde_json::from_value(authenticator.create(&options)).unwrap();
+
+    assert_eq!(webauthn::client_data_challenge(&credential.response.client_data_json).unwrap(), challenge);
+    webauthn::verify_registration(rp, &challenge, &credential).unwrap()
+}
+
+fn login(
+    rp: &RelyingParty,
+    authenticator: &mut SoftAuthenticator,
+    stored: &VerifiedCredential,
+) -> Result<u32, AppError> {
+    let challenge = webauthn::new_challenge();
+    let options = webauthn::request_options(rp, &challenge);
+    let credential: AssertionCredential = serde_json::from_value(authenticator.get(&options)).unwrap();
+
+    webauthn::verify_assertion(rp, &challenge, &credential, stored.algorithm, &stored.public_key, stored.sign_count)
+}
+
+#[test]
+fn test_relying_party_id_is_the_origin_host() {
+    let rp = RelyingParty::from_origin("https://agency.example.com:8443/", "Recruitment Agency").unwrap();
+
+    assert_eq!(rp.id, "agency.example.com");
+    assert_eq!(rp.origin, "https://agency.example.com:8443");
+    assert!(RelyingParty::from_origin("not a url", "Recruitment Agency").is_none());
+}
+
+#[test]
+fn test_registration_and_login_round_trip() {
+    let rp = relying_party();
+    let mut authenticator = SoftAuthenticator::new(&rp.id, ORIGIN);
+
+    let mut stored = register(&rp, &mut authenticator);
+    assert_eq!(stored.credential_id, authenticator.credential_id());
+    assert_eq!(stored.algorithm, COSE_ALG_ES256);
+    assert_eq!(stored.sign_count, 0);
+
+    stored.sign_count = login(&rp, &mut authenticator, &stored).unwrap();
+    assert_eq!(stored.sign_count, 1);
+    assert_eq!(login(&rp, &mut authenticator, &stored).unwrap(), 2);
+}
+
+#[test]
+fn test_responses_from_another_origin_are_refused() {
+    let rp = relying_party();
+    let mut authenticator = SoftAuthenticator::new(&rp.id, "https://agency.example.com.evil.test");
+
+    let challenge = webauthn::new_challenge();
+    let options = webauthn::creation_options(&rp, Uuid::new_v4(), "worker@example.com", &challenge, &[]);
+    let credential: RegistrationCredential = serde_json::from_value(authenticator.create(&options)).unwrap();
+
+    assert!(matches!(
+        webauthn::verify_registration(&rp, &challenge, &credential),
+        Err(AppError::BadRequest(_))
+    ));
+}
+
+#[test]
+fn test_credentials_for_another_site_are_refused() {
+    let rp = relying_party();
+    let mut authenticator = SoftAuthenticator::new("evil.test", ORIGIN);
+
+    let challenge = webauthn::new_challenge();
+    let options = webauthn::creation_options(&rp, Uuid::new_v4(), "worker@example.com", &challenge, &[]);
+    let credential: RegistrationCredential = serde_json::from_value(authenticator.create(&options)).unwrap();
+
+    assert!(webauthn::verify_registration(&rp, &challenge, &credential).is_err());
+}
+
+#[test]
+fn test_assertions_for_another_challenge_are_refused() {
+    let rp = relying_party();
+    let mut authenticator = SoftAuthenticator::new(&rp.id, ORIGIN);
+    let stored = register(&rp, &mut authenticator);
+
+    let options = webauthn::request_options(&rp, &webauthn::new_challenge());
+    let credential: AssertionCredential = serde_json::from_value(authenticator.get(&options)).unwrap();
+
+    let other_challenge = webauthn::new_challenge();
+    assert!(webauthn::verify_assertion(
+        &rp,
+        &other_challenge,
+        &credential,
+        stored.algorithm,
+        &stored.public_key,
+        stored.sign_count
+    )
+    .is_err());
+}
+
+#[test]
+fn test_assertions_signed_by_another_key_are_refused() {
+    let rp = relying_party();
+    let mut authenticator = SoftAuthenticator::new(&rp.id, ORIGIN);
+    let stored = register(&rp, &mut authenticator);
+
+    let mut impostor = SoftAuthenticator::new(&rp.id, ORIGIN);
+    assert!(matches!(login(&rp, &mut impostor, &stored), Err(AppError::Unauthorized)));
+}
+
+#[test]
+fn test_sign_counter_regression_is_refused() {
+    let rp = relying_party();
+    let mut authenticator = SoftAuthenticator::new(&rp.id, ORIGIN);
+    let mut stored = register(&rp, &mut authenticator);
+
+    stored.sign_count = login(&rp, &mut authenticator, &stored).unwrap();
+
+    // A clone of the key that is behind the original
+    authenticator.sign_count = 0;
+    assert!(matches!(login(&rp, &mut authenticator, &stored), Err(AppError::Unauthorized)));
+}